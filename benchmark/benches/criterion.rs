@@ -1,5 +1,19 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 
+fn day08_mst_benchmark(c: &mut Criterion) {
+    use day08::{Mst, SpatialIndex, SquaredEuclidean};
+
+    // Only the spanning tree depends on the backend, so leave parsing and indexing out of the measurements.
+    let boxes = day08::parse_boxes::<3>(include_str!("../../day08/src/input.txt"), SquaredEuclidean).unwrap();
+    let index = SpatialIndex::new(&boxes, SquaredEuclidean).unwrap();
+
+    let mut group = c.benchmark_group("day08_mst");
+    for mst in [Mst::Kruskal, Mst::Boruvka] {
+        group.bench_function(format!("{:?}", mst), |b| b.iter(|| index.spanning_tree(mst)));
+    }
+    group.finish();
+}
+
 macro_rules! doit {
     ($($day:ident),*$(,)?) => {
         pub fn aoc_benchmark(c: &mut Criterion) {
@@ -12,7 +26,7 @@ macro_rules! doit {
 
             config = Criterion::default();
 
-            targets = aoc_benchmark
+            targets = aoc_benchmark, day08_mst_benchmark
         }
 
         criterion_main!(benches);
@@ -0,0 +1,274 @@
+/// What lies beyond the edges of a [`BitGrid`] when counting neighbors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Edges {
+    /// Out-of-bounds cells are empty floor.
+    #[default]
+    Empty,
+    /// The grid wraps around on itself, like a torus.
+    Wrap,
+    /// Out-of-bounds cells count as occupied.
+    Occupied,
+}
+
+/// A grid of booleans packed one bit per cell, 64 cells to a word.
+///
+/// Bit `x % 64` of word `x / 64` in a row holds cell `x`; the unused high bits of each row's last word are always
+/// zero, so shifts across the right edge pull in empty cells for free.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    stride: usize,
+    words: Vec<u64>,
+    edges: Edges,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+            edges: Edges::Empty,
+        }
+    }
+
+    pub fn with_edges(self, edges: Edges) -> Self {
+        Self { edges, ..self }
+    }
+
+    /// Parses a map where `@` marks a roll of paper; every other non-whitespace byte is empty floor.
+    pub fn parse(input: &str) -> Self {
+        let width = input.lines().next().map_or(0, |line| line.trim().len());
+        let height = input.lines().filter(|line| !line.trim().is_empty()).count();
+        let mut grid = Self::new(width, height);
+
+        for (y, line) in input.lines().filter(|line| !line.trim().is_empty()).enumerate() {
+            grid.parse_row(y, line.as_bytes());
+        }
+
+        grid
+    }
+
+    /// Overwrites row `y` with the contents of `line`, which is trimmed and cut down to the grid's width.
+    pub(crate) fn parse_row(&mut self, y: usize, line: &[u8]) {
+        let line = line.trim_ascii();
+        let line = &line[..line.len().min(self.width)];
+        let row = self.row_mut(y);
+        row.fill(0);
+        for (word, chunk) in row.iter_mut().zip(line.chunks(64)) {
+            *word = chunk.iter().rev().fold(0, |acc, &b| acc << 1 | u64::from(b == b'@'));
+        }
+    }
+
+    /// Shifts every row up by one, moving the first row to the bottom.
+    pub(crate) fn rotate_rows(&mut self) {
+        self.words.rotate_left(self.stride);
+    }
+
+    /// Overwrites row `y` so that every cell is `value`.
+    pub(crate) fn fill_row(&mut self, y: usize, value: bool) {
+        let width = self.width;
+        for (i, word) in self.row_mut(y).iter_mut().enumerate() {
+            let bits = (width - 64 * i).min(64);
+            *word = if value { u64::MAX >> (64 - bits) } else { 0 };
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, y: usize, x: usize) -> bool {
+        x < self.width && self.word(y, x / 64) >> (x % 64) & 1 != 0
+    }
+
+    pub(crate) fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..][..self.stride]
+    }
+
+    pub(crate) fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.stride..][..self.stride]
+    }
+
+    pub fn edges(&self) -> Edges {
+        self.edges
+    }
+
+    /// Returns the row `dy` rows away from `y`, or `None` if that falls off the grid.
+    fn row_offset(&self, y: usize, dy: isize) -> Option<usize> {
+        match self.edges {
+            Edges::Wrap => Some((y + self.height).wrapping_add_signed(dy) % self.height),
+            Edges::Empty | Edges::Occupied => Some(y.wrapping_add_signed(dy)).filter(|&ny| ny < self.height),
+        }
+    }
+
+    /// Returns the contents of the out-of-bounds cell at column `x` of row `y`, either `0` or `1`.
+    fn beyond(&self, y: usize, x: isize) -> u64 {
+        match self.edges {
+            Edges::Empty => 0,
+            Edges::Occupied => 1,
+            Edges::Wrap => u64::from(self.get(y, x.rem_euclid(self.width as isize) as usize)),
+        }
+    }
+
+    fn word(&self, y: usize, i: usize) -> u64 {
+        if y < self.height && i < self.stride {
+            self.words[y * self.stride + i]
+        } else {
+            0
+        }
+    }
+
+    /// Returns the mask of occupied cells in word `i` of row `y` that have fewer than four occupied neighbors.
+    #[inline]
+    pub fn accessible_word(&self, y: usize, i: usize) -> u64 {
+        if y == 0 || y + 1 >= self.height || i == 0 || i + 1 >= self.stride {
+            return self.accessible_word_slow(y, i);
+        }
+
+        let idx = y * self.stride + i;
+        let block = |idx: usize| -> [u64; 3] { self.words[idx - 1..=idx + 1].try_into().unwrap() };
+        let [ul, u, ur] = block(idx - self.stride);
+        let [l, c, r] = block(idx);
+        let [dl, d, dr] = block(idx + self.stride);
+        c & count_below_four(around(ul, u, ur), around(l, c, r), around(dl, d, dr))
+    }
+
+    /// Like [`BitGrid::accessible_word`], but bounds-checked and applying the edge policy.
+    #[cold]
+    fn accessible_word_slow(&self, y: usize, i: usize) -> u64 {
+        let last = (self.width - 1) % 64;
+        let row = |dy| {
+            let Some(y) = self.row_offset(y, dy) else {
+                let fill = if self.edges == Edges::Occupied { u64::MAX } else { 0 };
+                return [fill; 3];
+            };
+
+            // Out-of-bounds words read back as empty, so only the cells just past either side need patching up.
+            let [mut west, cur, mut east] =
+                around(self.word(y, i.wrapping_sub(1)), self.word(y, i), self.word(y, i + 1));
+            if i == 0 {
+                west = west & !1 | self.beyond(y, -1);
+            }
+            if i == self.stride - 1 {
+                east = east & !(1 << last) | self.beyond(y, self.width as isize) << last;
+            }
+            [west, cur, east]
+        };
+        self.word(y, i) & count_below_four(row(-1), row(0), row(1))
+    }
+
+    /// Counts the occupied cells in row `y` with fewer than four occupied neighbors.
+    pub fn count_accessible_row(&self, y: usize) -> usize {
+        (0..self.stride)
+            .map(|i| self.accessible_word(y, i).count_ones() as usize)
+            .sum()
+    }
+
+    /// Counts the occupied cells with fewer than four occupied neighbors.
+    pub fn count_accessible(&self) -> usize {
+        (0..self.height).map(|y| self.count_accessible_row(y)).sum()
+    }
+
+    /// Repeatedly removes accessible cells until none are left, returning how many were removed.
+    ///
+    /// Removing a cell only ever lowers its neighbors' counts, so the order of removal doesn't affect the end result;
+    /// this lets us clear a whole word at a time and only revisit the words next to one that changed.
+    pub fn remove_accessible(&mut self) -> usize {
+        let mut removed = 0;
+        let mut dirty = vec![true; self.words.len()];
+        let mut any_dirty = true;
+
+        // Sweep in memory order, clearing dirty words as we go: neighbors ahead of us get picked up in this same sweep,
+        // the ones behind us in the next.
+        while any_dirty {
+            any_dirty = false;
+            for idx in 0..self.words.len() {
+                if !dirty[idx] {
+                    continue;
+                }
+                dirty[idx] = false;
+                let (y, i) = (idx / self.stride, idx % self.stride);
+
+                let mut cleared = 0;
+                loop {
+                    let mask = self.accessible_word(y, i);
+                    if mask == 0 {
+                        break;
+                    }
+                    self.words[idx] &= !mask;
+                    cleared |= mask;
+                    removed += mask.count_ones() as usize;
+                }
+                if cleared == 0 {
+                    continue;
+                }
+
+                // Only spill over into the neighboring words if a cell on the matching edge was cleared.
+                let wrap = self.edges == Edges::Wrap;
+                let west = match i {
+                    0 if wrap && cleared & 1 != 0 => Some(self.stride - 1),
+                    0 => None,
+                    _ => (cleared & 1 != 0).then(|| i - 1),
+                };
+                let east = if i == self.stride - 1 {
+                    (wrap && cleared >> ((self.width - 1) % 64) & 1 != 0).then_some(0)
+                } else {
+                    (cleared >> 63 != 0).then_some(i + 1)
+                };
+
+                for ny in [-1, 0, 1].into_iter().filter_map(|dy| self.row_offset(y, dy)) {
+                    for ni in [west, Some(i), east].into_iter().flatten() {
+                        let nidx = ny * self.stride + ni;
+                        if nidx != idx {
+                            dirty[nidx] = true;
+                            any_dirty |= nidx < idx;
+                        }
+                    }
+                }
+            }
+        }
+
+        removed
+    }
+}
+
+#[inline(always)]
+fn half_add(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+#[inline(always)]
+fn full_add(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let (s, c1) = half_add(a, b);
+    let (s, c2) = half_add(s, c);
+    (s, c1 | c2)
+}
+
+/// Returns the west, center and east neighbors of each cell in word `cur`, given the words either side of it.
+#[inline(always)]
+fn around(prev: u64, cur: u64, next: u64) -> [u64; 3] {
+    [cur << 1 | prev >> 63, cur, cur >> 1 | next << 63]
+}
+
+/// Bitsliced population count of the eight neighbors of each lane, returning the lanes whose total is below four.
+///
+/// The sum fits in four bits; it's below four exactly when neither of the two carries into the fours place is set.
+/// The center of the middle row is the cell itself, and is left out of the count.
+#[inline(always)]
+fn count_below_four([a, b, c]: [u64; 3], [d, _, e]: [u64; 3], [f, g, h]: [u64; 3]) -> u64 {
+    let (ones1, twos1) = full_add(a, b, c);
+    let (ones2, twos2) = full_add(d, e, f);
+    let (ones3, twos3) = half_add(g, h);
+    let (_, twos4) = full_add(ones1, ones2, ones3);
+    let (twos, fours1) = full_add(twos1, twos2, twos3);
+    let (_, fours2) = half_add(twos, twos4);
+    !(fours1 | fours2)
+}
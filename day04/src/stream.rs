@@ -0,0 +1,63 @@
+use std::io::{self, BufRead};
+
+use crate::{BitGrid, Edges};
+
+/// Counts the accessible rolls of paper (part 1) in a map read from `reader`, keeping only a few rows in memory.
+pub fn count_accessible_streaming(mut reader: impl BufRead, edges: Edges) -> io::Result<usize> {
+    let mut line = Vec::new();
+    let mut next_line = |line: &mut Vec<u8>| -> io::Result<bool> {
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', line)? == 0 {
+                return Ok(false);
+            }
+            if !line.trim_ascii().is_empty() {
+                return Ok(true);
+            }
+        }
+    };
+
+    if !next_line(&mut line)? {
+        return Ok(0);
+    }
+
+    // Rows 0, 1 and 2 of the window hold the rows above, at and below the one being counted.
+    let mut window = BitGrid::new(line.trim_ascii().len(), 3).with_edges(edges);
+    window.parse_row(1, &line);
+    match edges {
+        Edges::Empty | Edges::Occupied => window.fill_row(0, edges == Edges::Occupied),
+        // Only correct if the map turns out to be a single row; otherwise row 0 gets counted at the very end.
+        Edges::Wrap => window.parse_row(0, &line),
+    }
+
+    let first = window.row(1).to_vec();
+    let mut second = None;
+    let mut count = 0;
+    let mut y = 0;
+
+    while next_line(&mut line)? {
+        window.parse_row(2, &line);
+        if y == 0 && edges == Edges::Wrap {
+            second = Some(window.row(2).to_vec());
+        } else {
+            count += window.count_accessible_row(1);
+        }
+
+        window.rotate_rows();
+        y += 1;
+    }
+
+    match edges {
+        Edges::Empty | Edges::Occupied => window.fill_row(2, edges == Edges::Occupied),
+        Edges::Wrap => window.row_mut(2).copy_from_slice(&first),
+    }
+    count += window.count_accessible_row(1);
+
+    if let Some(second) = second {
+        window.rotate_rows();
+        window.row_mut(2).copy_from_slice(&second);
+        count += window.count_accessible_row(1);
+    }
+
+    Ok(count)
+}
@@ -0,0 +1,57 @@
+use std::{error::Error, fmt, ops::RangeInclusive, str::FromStr};
+
+/// The puzzle input: a list of fresh ingredient ID ranges, then a blank line, then the available ingredient IDs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Database {
+    pub ranges: Vec<RangeInclusive<u64>>,
+    pub available: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A range line that isn't two IDs separated by `-`.
+    MalformedRange { line: usize },
+    /// A range whose end comes before its start, e.g. `10-5`.
+    InvertedRange { line: usize, start: u64, end: u64 },
+    /// An ID that isn't a decimal number fitting in a `u64`.
+    InvalidId { line: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MalformedRange { line } => write!(f, "line {line}: expected a range like `3-5`"),
+            ParseError::InvertedRange { line, start, end } => {
+                write!(f, "line {line}: range {start}-{end} ends before it starts")
+            }
+            ParseError::InvalidId { line } => write!(f, "line {line}: invalid ingredient ID"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl FromStr for Database {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut db = Database::default();
+        let mut lines = s.lines().map(str::trim).enumerate().map(|(idx, line)| (idx + 1, line));
+        let parse_id = |line, id: &str| id.parse::<u64>().map_err(|_| ParseError::InvalidId { line });
+
+        for (line, range) in lines.by_ref().take_while(|(_, range)| !range.is_empty()) {
+            let (start, end) = range.split_once('-').ok_or(ParseError::MalformedRange { line })?;
+            let (start, end) = (parse_id(line, start)?, parse_id(line, end)?);
+            if start > end {
+                return Err(ParseError::InvertedRange { line, start, end });
+            }
+            db.ranges.push(start..=end);
+        }
+
+        for (line, id) in lines.filter(|(_, id)| !id.is_empty()) {
+            db.available.push(parse_id(line, id)?);
+        }
+
+        Ok(db)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::{Database, RangeId, RangeIndex};
+
+/// Why each available ingredient is fresh, and which ranges add nothing to the database.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Explanation {
+    /// For each available ID, in input order, the sorted indices of the ranges containing it; empty if it's spoiled.
+    pub containing: Vec<Vec<usize>>,
+    /// Every range whose values are all covered by the other ranges together, in input order.
+    pub redundant: Vec<Redundant>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Redundant {
+    /// The index of the redundant range.
+    pub range: usize,
+    /// The sorted indices of the other ranges overlapping it, which between them cover all of it.
+    pub covered_by: Vec<usize>,
+}
+
+impl Database {
+    pub fn explain(&self) -> Explanation {
+        let index = self.ranges.iter().cloned().collect::<RangeIndex>();
+        let indices = |ids: Vec<RangeId>, skip: Option<usize>| {
+            let mut ranges = ids
+                .into_iter()
+                .map(|range| range.index())
+                .filter(|&i| Some(i) != skip)
+                .collect::<Vec<_>>();
+            ranges.sort_unstable();
+            ranges
+        };
+
+        let containing = self.available.iter().map(|&id| indices(index.stab(id), None)).collect();
+
+        // A range adds nothing exactly when every value in it is covered by at least one other range as well.
+        let redundant = (self.ranges.iter().enumerate())
+            .filter(|(_, range)| index.depth((*range).clone()) >= 2)
+            .map(|(i, range)| Redundant {
+                range: i,
+                covered_by: indices(index.overlapping(range.clone()), Some(i)),
+            })
+            .collect();
+
+        Explanation { containing, redundant }
+    }
+}
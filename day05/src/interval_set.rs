@@ -0,0 +1,187 @@
+use std::ops::RangeInclusive;
+
+/// A set of `u64`s stored as a sorted list of inclusive ranges, with any that overlap or touch merged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<(u64, u64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds every value in `range` to the set.
+    pub fn insert(&mut self, range: RangeInclusive<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        let (mut start, mut end) = range.into_inner();
+
+        // Every range from `lo` up to (but excluding) `hi` overlaps or touches the new one.
+        let lo = self.ranges.partition_point(|&(_, b)| b.saturating_add(1) < start);
+        let hi = self.ranges.partition_point(|&(a, _)| a <= end.saturating_add(1));
+        if lo < hi {
+            start = start.min(self.ranges[lo].0);
+            end = end.max(self.ranges[hi - 1].1);
+        }
+        self.ranges.splice(lo..hi, [(start, end)]);
+    }
+
+    /// Removes every value in `range` from the set.
+    pub fn remove(&mut self, range: RangeInclusive<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        let (start, end) = range.into_inner();
+
+        // Every range from `lo` up to (but excluding) `hi` overlaps the removed one.
+        let lo = self.ranges.partition_point(|&(_, b)| b < start);
+        let hi = self.ranges.partition_point(|&(a, _)| a <= end);
+        if lo == hi {
+            return;
+        }
+
+        let (first, last) = (self.ranges[lo].0, self.ranges[hi - 1].1);
+        let left = (first < start).then(|| (first, start - 1));
+        let right = (last > end).then(|| (end + 1, last));
+        self.ranges.splice(lo..hi, left.into_iter().chain(right));
+    }
+
+    pub fn contains(&self, n: u64) -> bool {
+        let idx = self.ranges.partition_point(|&(_, b)| b < n);
+        self.ranges.get(idx).is_some_and(|&(a, _)| a <= n)
+    }
+
+    /// Counts how many of the values in `sorted`, which must be in increasing order, are in the set.
+    pub fn count_sorted(&self, sorted: &[u64]) -> usize {
+        debug_assert!(sorted.is_sorted());
+        let mut ranges = self.ranges.iter().peekable();
+        sorted
+            .iter()
+            .filter(|&&n| {
+                while ranges.next_if(|&&(_, b)| b < n).is_some() {}
+                ranges.peek().is_some_and(|&&(a, _)| a <= n)
+            })
+            .count()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut ranges = Vec::with_capacity(self.ranges.len() + other.ranges.len());
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() || j < other.ranges.len() {
+            let next = match (self.ranges.get(i), other.ranges.get(j)) {
+                (Some(&a), Some(&b)) if a <= b => {
+                    i += 1;
+                    a
+                }
+                (_, Some(&b)) => {
+                    j += 1;
+                    b
+                }
+                (Some(&a), None) => {
+                    i += 1;
+                    a
+                }
+                (None, None) => unreachable!(),
+            };
+            push_merging(&mut ranges, next);
+        }
+        Self { ranges }
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(a1, b1)), Some(&(a2, b2))) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a1.max(a2), b1.min(b2));
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            // Whichever range ends first can't overlap anything else in the other set.
+            if b1 < b2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.intersection(&other.complement(0..=u64::MAX))
+    }
+
+    /// Returns the values within `bounds` that are not in the set.
+    pub fn complement(&self, bounds: RangeInclusive<u64>) -> Self {
+        let mut ranges = Vec::new();
+        if bounds.is_empty() {
+            return Self { ranges };
+        }
+        let (start, end) = bounds.into_inner();
+
+        // The next value that could start a gap, or `None` once we've run past `u64::MAX`.
+        let mut cursor = Some(start);
+        for &(a, b) in &self.ranges {
+            let Some(from) = cursor else { break };
+            if a > end {
+                break;
+            }
+            if a > from {
+                ranges.push((from, a - 1));
+            }
+            cursor = b.checked_add(1).map(|next| next.max(from));
+        }
+        if let Some(from) = cursor.filter(|&from| from <= end) {
+            ranges.push((from, end));
+        }
+
+        Self { ranges }
+    }
+
+    /// Returns how many values are in the set, which can be one more than `u64::MAX`.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|&(a, b)| u128::from(b - a) + 1).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterates over the disjoint ranges making up the set, in increasing order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = RangeInclusive<u64>> + '_ {
+        self.ranges.iter().map(|&(a, b)| a..=b)
+    }
+}
+
+/// Appends `next`, which must not start before the last range, merging the two if they overlap or touch.
+fn push_merging(ranges: &mut Vec<(u64, u64)>, next: (u64, u64)) {
+    match ranges.last_mut() {
+        Some(prev) if next.0 <= prev.1.saturating_add(1) => prev.1 = prev.1.max(next.1),
+        _ => ranges.push(next),
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(iter: I) -> Self {
+        let mut sorted = iter
+            .into_iter()
+            .filter(|range| !range.is_empty())
+            .map(RangeInclusive::into_inner)
+            .collect::<Vec<_>>();
+        sorted.sort_unstable();
+
+        let mut ranges = Vec::with_capacity(sorted.len());
+        for range in sorted {
+            push_merging(&mut ranges, range);
+        }
+        Self { ranges }
+    }
+}
+
+impl Extend<RangeInclusive<u64>> for IntervalSet {
+    fn extend<I: IntoIterator<Item = RangeInclusive<u64>>>(&mut self, iter: I) {
+        *self = self.union(&iter.into_iter().collect());
+    }
+}
@@ -1,42 +1,24 @@
 use std::fmt::Display;
 
-#[inline]
-pub fn solve() -> (impl Display, impl Display) {
-    let input = include_str!("input.txt");
-    let (ranges, tests) = input.split_once("\n\n").unwrap();
-    let mut ranges = ranges
-        .lines()
-        .map(|range| {
-            let (a, b) = range.split_once('-').unwrap();
-            (a.parse::<u64>().unwrap(), b.parse::<u64>().unwrap())
-        })
-        .collect::<Vec<_>>();
-
-    let part1 = tests
-        .lines()
-        .map(|s| s.parse::<u64>().unwrap())
-        .filter(|&n| ranges.iter().any(|&(start, end)| n >= start && n <= end))
-        .count();
+mod database;
+mod explain;
+mod interval_set;
+mod range_index;
 
-    ranges.sort_unstable();
+pub use database::{Database, ParseError};
+pub use explain::{Explanation, Redundant};
+pub use interval_set::IntervalSet;
+pub use range_index::{RangeId, RangeIndex};
 
-    let mut prev = ranges[0];
-    let mut part2 = 0;
-    for mut next in ranges.into_iter().skip(1) {
-        if prev.1 >= next.0 {
-            next.1 = next.1.max(prev.1);
-            prev.1 = next.0 - 1;
-        }
+#[inline]
+pub fn solve() -> (impl Display, impl Display) {
+    let Database { ranges, mut available } = include_str!("input.txt").parse().unwrap();
+    let fresh = ranges.into_iter().collect::<IntervalSet>();
 
-        if prev.0 <= prev.1 {
-            part2 += prev.1 - prev.0 + 1;
-        }
+    available.sort_unstable();
+    let part1 = fresh.count_sorted(&available);
 
-        prev = next;
-    }
-    if prev.0 <= prev.1 {
-        part2 += prev.1 - prev.0 + 1;
-    }
+    let part2 = fresh.len();
 
     (part1, part2)
 }
@@ -0,0 +1,263 @@
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+/// Identifies a range inserted into a [`RangeIndex`]; handles are never reused, even after the range is removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RangeId(usize);
+
+impl RangeId {
+    /// Returns how many ranges were inserted into the index before this one.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A dynamic collection of possibly overlapping ranges supporting insertion, removal and stabbing queries.
+///
+/// The ranges live in a treap ordered by start and augmented with the largest end in each subtree, which lets
+/// [`RangeIndex::stab`] skip every subtree that ends before the queried value. Alongside it, a map from breakpoints to
+/// how many ranges cover the segment starting there keeps the number of covered values up to date.
+#[derive(Clone, Debug, Default)]
+pub struct RangeIndex {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    len: usize,
+    coverage: BTreeMap<u64, usize>,
+    covered: u128,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    start: u64,
+    end: u64,
+    priority: u64,
+    max_end: u64,
+    left: Option<usize>,
+    right: Option<usize>,
+    removed: bool,
+}
+
+impl RangeIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns how many ranges are currently in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns how many distinct values are covered by at least one range.
+    pub fn covered(&self) -> u128 {
+        self.covered
+    }
+
+    /// Returns whether any range contains `n`.
+    pub fn contains(&self, n: u64) -> bool {
+        self.coverage
+            .range(..=n)
+            .next_back()
+            .is_some_and(|(_, &count)| count > 0)
+    }
+
+    pub fn get(&self, id: RangeId) -> Option<RangeInclusive<u64>> {
+        self.nodes
+            .get(id.0)
+            .filter(|node| !node.removed)
+            .map(|node| node.start..=node.end)
+    }
+
+    /// Adds `range`, which must not be empty, returning a handle that can later be used to remove it.
+    pub fn insert(&mut self, range: RangeInclusive<u64>) -> RangeId {
+        assert!(!range.is_empty(), "cannot insert an empty range");
+        let (start, end) = range.into_inner();
+
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            start,
+            end,
+            priority: splitmix64(id as u64),
+            max_end: end,
+            left: None,
+            right: None,
+            removed: false,
+        });
+
+        let (left, right) = self.split(self.root, (start, id));
+        let left = self.merge(left, Some(id));
+        self.root = self.merge(left, right);
+        self.len += 1;
+
+        self.add_coverage(start, end, true);
+        RangeId(id)
+    }
+
+    /// Removes the range with handle `id`, returning it if it was still in the index.
+    pub fn remove(&mut self, id: RangeId) -> Option<RangeInclusive<u64>> {
+        let range = self.get(id)?;
+        let (start, end) = (*range.start(), *range.end());
+
+        let (left, rest) = self.split(self.root, (start, id.0));
+        let (node, right) = self.split(rest, (start, id.0 + 1));
+        debug_assert_eq!(node, Some(id.0));
+        self.root = self.merge(left, right);
+        self.nodes[id.0].removed = true;
+        self.len -= 1;
+
+        self.add_coverage(start, end, false);
+        Some(range)
+    }
+
+    /// Returns the handles of every range containing `n`, ordered by where the ranges start.
+    pub fn stab(&self, n: u64) -> Vec<RangeId> {
+        self.overlapping(n..=n)
+    }
+
+    /// Returns the handles of every range sharing at least one value with `range`, ordered by where they start.
+    pub fn overlapping(&self, range: RangeInclusive<u64>) -> Vec<RangeId> {
+        let mut found = Vec::new();
+        self.overlapping_rec(self.root, *range.start(), *range.end(), &mut found);
+        found
+    }
+
+    fn overlapping_rec(&self, node: Option<usize>, from: u64, to: u64, found: &mut Vec<RangeId>) {
+        let Some(idx) = node.filter(|&idx| self.nodes[idx].max_end >= from) else {
+            return;
+        };
+        let node = &self.nodes[idx];
+
+        self.overlapping_rec(node.left, from, to, found);
+        if node.start <= to {
+            if node.end >= from {
+                found.push(RangeId(idx));
+            }
+            self.overlapping_rec(node.right, from, to, found);
+        }
+    }
+
+    /// Returns the fewest ranges covering any single value in `range`.
+    pub fn depth(&self, range: RangeInclusive<u64>) -> usize {
+        let (start, end) = range.into_inner();
+        let first = self.coverage.range(..=start).next_back().map_or(0, |(_, &count)| count);
+        self.coverage
+            .range(start..=end)
+            .map(|(_, &count)| count)
+            .fold(first, usize::min)
+    }
+
+    /// Splits the treap rooted at `node` into the nodes ordered before `key` and the rest.
+    fn split(&mut self, node: Option<usize>, key: (u64, usize)) -> (Option<usize>, Option<usize>) {
+        let Some(idx) = node else {
+            return (None, None);
+        };
+
+        if (self.nodes[idx].start, idx) < key {
+            let (left, right) = self.split(self.nodes[idx].right, key);
+            self.nodes[idx].right = left;
+            self.update(idx);
+            (Some(idx), right)
+        } else {
+            let (left, right) = self.split(self.nodes[idx].left, key);
+            self.nodes[idx].left = right;
+            self.update(idx);
+            (left, Some(idx))
+        }
+    }
+
+    /// Joins two treaps, where every node in `left` is ordered before every node in `right`.
+    fn merge(&mut self, left: Option<usize>, right: Option<usize>) -> Option<usize> {
+        let (Some(l), Some(r)) = (left, right) else {
+            return left.or(right);
+        };
+
+        if self.nodes[l].priority > self.nodes[r].priority {
+            self.nodes[l].right = self.merge(self.nodes[l].right, right);
+            self.update(l);
+            Some(l)
+        } else {
+            self.nodes[r].left = self.merge(left, self.nodes[r].left);
+            self.update(r);
+            Some(r)
+        }
+    }
+
+    fn update(&mut self, idx: usize) {
+        let node = &self.nodes[idx];
+        let max_end = [node.left, node.right]
+            .into_iter()
+            .flatten()
+            .map(|child| self.nodes[child].max_end)
+            .fold(node.end, u64::max);
+        self.nodes[idx].max_end = max_end;
+    }
+
+    /// Adds or subtracts one from the coverage count of every value in `start..=end`, keeping `covered` in sync.
+    fn add_coverage(&mut self, start: u64, end: u64, add: bool) {
+        let after = end.checked_add(1);
+        self.breakpoint(start);
+        if let Some(after) = after {
+            self.breakpoint(after);
+        }
+
+        let mut segments = self.coverage.range_mut(start..=end).peekable();
+        while let Some((&from, count)) = segments.next() {
+            let to = segments.peek().map_or(end, |&(&next, _)| next - 1);
+            let len = u128::from(to - from) + 1;
+            if add {
+                *count += 1;
+                if *count == 1 {
+                    self.covered += len;
+                }
+            } else {
+                *count -= 1;
+                if *count == 0 {
+                    self.covered -= len;
+                }
+            }
+        }
+
+        // Every segment in between changed by the same amount, so only the two ends can have become redundant.
+        self.coalesce(start);
+        if let Some(after) = after {
+            self.coalesce(after);
+        }
+    }
+
+    /// Makes sure a segment starts exactly at `at`, splitting the one containing it if needed.
+    fn breakpoint(&mut self, at: u64) {
+        let count = self.coverage.range(..=at).next_back().map_or(0, |(_, &count)| count);
+        self.coverage.entry(at).or_insert(count);
+    }
+
+    /// Removes the breakpoint at `at` if the segment starting there has the same count as the one before it.
+    fn coalesce(&mut self, at: u64) {
+        let Some(&count) = self.coverage.get(&at) else {
+            return;
+        };
+        let before = self.coverage.range(..at).next_back().map_or(0, |(_, &count)| count);
+        if before == count {
+            self.coverage.remove(&at);
+        }
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for RangeIndex {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(iter: I) -> Self {
+        let mut index = Self::new();
+        for range in iter {
+            index.insert(range);
+        }
+        index
+    }
+}
+
+/// Scrambles `x` into a well-distributed pseudorandom value, used as a deterministic source of treap priorities.
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
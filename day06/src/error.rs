@@ -0,0 +1,84 @@
+use std::{error::Error, fmt};
+
+/// Something wrong with the layout of a worksheet. Lines and columns count from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The worksheet needs at least one row of numbers and a row of operators.
+    TooFewLines,
+    /// A problem's leftmost column has no operator underneath it.
+    MissingOperator {
+        line: usize,
+        column: usize,
+    },
+    UnknownOperator {
+        line: usize,
+        column: usize,
+        glyph: char,
+    },
+    /// Something other than a digit or a space among the numbers, or anything but a space to the right of an operator.
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// A row of a problem with no number in it; `column` is the problem's leftmost column.
+    MissingNumber {
+        line: usize,
+        column: usize,
+    },
+    /// A row of a problem with spaces in between its digits, so it can't be read as a single number.
+    SplitNumber {
+        line: usize,
+        column: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::TooFewLines => write!(f, "expected rows of numbers followed by a row of operators"),
+            ParseError::MissingOperator { line, column } => {
+                write!(f, "line {line}, column {column}: expected an operator")
+            }
+            ParseError::UnknownOperator { line, column, glyph } => {
+                write!(f, "line {line}, column {column}: unknown operator {glyph:?}")
+            }
+            ParseError::UnexpectedCharacter { line, column, found } => {
+                write!(f, "line {line}, column {column}: unexpected {found:?}")
+            }
+            ParseError::MissingNumber { line, column } => {
+                write!(f, "line {line}, column {column}: expected a number")
+            }
+            ParseError::SplitNumber { line, column } => {
+                write!(f, "line {line}, column {column}: expected a single number, found a gap")
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EvalErrorKind {
+    Overflow,
+    DivisionByZero,
+}
+
+/// A problem that couldn't be evaluated, identified by its index on the worksheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EvalError {
+    pub problem: usize,
+    pub kind: EvalErrorKind,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let problem = self.problem;
+        match self.kind {
+            EvalErrorKind::Overflow => write!(f, "problem {problem} overflowed"),
+            EvalErrorKind::DivisionByZero => write!(f, "problem {problem} divides by zero"),
+        }
+    }
+}
+
+impl Error for EvalError {}
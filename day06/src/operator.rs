@@ -0,0 +1,83 @@
+use crate::EvalErrorKind;
+
+/// An operator from the bottom row of the worksheet, folding its operands left to right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`, rounding towards zero.
+    Div,
+    /// `%`, taking the sign of the dividend.
+    Rem,
+    /// `^`
+    Pow,
+    /// `<`, the smallest operand.
+    Min,
+    /// `>`, the largest operand.
+    Max,
+}
+
+impl Operator {
+    pub fn from_glyph(glyph: u8) -> Option<Self> {
+        Some(match glyph {
+            b'+' => Operator::Add,
+            b'-' => Operator::Sub,
+            b'*' => Operator::Mul,
+            b'/' => Operator::Div,
+            b'%' => Operator::Rem,
+            b'^' => Operator::Pow,
+            b'<' => Operator::Min,
+            b'>' => Operator::Max,
+            _ => return None,
+        })
+    }
+
+    pub fn glyph(self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Sub => '-',
+            Operator::Mul => '*',
+            Operator::Div => '/',
+            Operator::Rem => '%',
+            Operator::Pow => '^',
+            Operator::Min => '<',
+            Operator::Max => '>',
+        }
+    }
+
+    /// Folds `operands` with this operator, checking every step; no operands evaluate to zero.
+    pub fn apply(self, operands: impl IntoIterator<Item = i128>) -> Result<i128, EvalErrorKind> {
+        let mut operands = operands.into_iter();
+        let Some(first) = operands.next() else {
+            return Ok(0);
+        };
+
+        operands.try_fold(first, |acc, n| match self {
+            Operator::Add => acc.checked_add(n).ok_or(EvalErrorKind::Overflow),
+            Operator::Sub => acc.checked_sub(n).ok_or(EvalErrorKind::Overflow),
+            Operator::Mul => acc.checked_mul(n).ok_or(EvalErrorKind::Overflow),
+            Operator::Div | Operator::Rem if n == 0 => Err(EvalErrorKind::DivisionByZero),
+            Operator::Div => acc.checked_div(n).ok_or(EvalErrorKind::Overflow),
+            Operator::Rem => acc.checked_rem(n).ok_or(EvalErrorKind::Overflow),
+            Operator::Pow => checked_pow(acc, n).ok_or(EvalErrorKind::Overflow),
+            Operator::Min => Ok(acc.min(n)),
+            Operator::Max => Ok(acc.max(n)),
+        })
+    }
+}
+
+/// Raises `base` to the power `exp`, treating negative exponents as an overflow.
+fn checked_pow(base: i128, exp: i128) -> Option<i128> {
+    if exp < 0 {
+        return None;
+    }
+    match base {
+        0 | 1 => Some(if exp == 0 { 1 } else { base }),
+        -1 => Some(if exp % 2 == 0 { 1 } else { -1 }),
+        _ => base.checked_pow(u32::try_from(exp).ok()?),
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt::Write;
+
+use crate::{EvalErrorKind, Problem, Reading, Worksheet};
+
+impl Worksheet<'_> {
+    /// Reprints every problem in its own box with its operands and results, wrapping lines at `max_width`.
+    pub fn render(&self, max_width: usize) -> String {
+        let mut out = String::new();
+        let mut band: Vec<Vec<String>> = Vec::new();
+        let mut band_width = 0;
+
+        for problem in self.problems() {
+            let block = self.render_problem(problem);
+            let width = block[0].chars().count();
+            if !band.is_empty() && band_width + 1 + width > max_width {
+                flush(&mut out, &mut band);
+                band_width = 0;
+            }
+            band_width += if band.is_empty() { width } else { width + 1 };
+            band.push(block);
+        }
+        flush(&mut out, &mut band);
+
+        out
+    }
+
+    /// Renders a single problem as equally wide lines of a box.
+    fn render_problem(&self, problem: &Problem) -> Vec<String> {
+        let glyph = problem.operator.glyph();
+        let mut sections = vec![
+            (0..self.height())
+                .map(|y| self.row_cells(y, problem).to_string())
+                .chain([glyph.to_string()])
+                .collect::<Vec<_>>(),
+        ];
+
+        for (name, reading) in [("rows", Reading::Rows), ("columns", Reading::Columns)] {
+            let mut lines = vec![name.to_string()];
+            let evaluated = self
+                .operands(problem, reading)
+                .and_then(|operands| Ok((problem.operator.apply(operands.iter().copied())?, operands)));
+            match evaluated {
+                Ok((result, operands)) => {
+                    let digits = operands
+                        .iter()
+                        .chain([&result])
+                        .map(|n| n.to_string().len())
+                        .max()
+                        .unwrap_or(0);
+                    for (i, n) in operands.iter().enumerate() {
+                        let sign = if i == 0 { ' ' } else { glyph };
+                        lines.push(format!("{sign} {n:>digits$}"));
+                    }
+                    lines.push(format!("= {result:>digits$}"));
+                }
+                Err(EvalErrorKind::Overflow) => lines.push("= overflow".to_string()),
+                Err(EvalErrorKind::DivisionByZero) => lines.push("= division by zero".to_string()),
+            }
+            sections.push(lines);
+        }
+
+        let inner = sections
+            .iter()
+            .flatten()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let rule = "─".repeat(inner);
+        let mut block = vec![format!("┌{rule}┐")];
+        for (i, section) in sections.iter().enumerate() {
+            if i > 0 {
+                block.push(format!("├{rule}┤"));
+            }
+            // Operands and results are right-aligned, so that the digits of every reading line up.
+            block.extend(section.iter().enumerate().map(|(j, line)| match (i, j) {
+                (0, _) | (_, 0) => format!("│{line:<inner$}│"),
+                _ => format!("│{line:>inner$}│"),
+            }));
+        }
+        block.push(format!("└{rule}┘"));
+        block
+    }
+}
+
+/// Writes a band of boxes side by side, padding the shorter ones with blank lines, and empties it.
+fn flush(out: &mut String, band: &mut Vec<Vec<String>>) {
+    let height = band.iter().map(Vec::len).max().unwrap_or(0);
+    for y in 0..height {
+        let line = band
+            .iter()
+            .map(|block| {
+                let width = block[0].chars().count();
+                format!("{:<width$}", block.get(y).map_or("", String::as_str))
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+    band.clear();
+}
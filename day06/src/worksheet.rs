@@ -0,0 +1,236 @@
+use std::{borrow::Cow, ops::Range};
+
+use atoi::FromRadix10Checked;
+
+use crate::{EvalError, EvalErrorKind, Operator, ParseError};
+
+/// How to read the numbers of a problem off the worksheet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reading {
+    /// One number per row, top to bottom, like a human would.
+    Rows,
+    /// One number per column, right to left, with the most significant digit at the top, like a cephalopod would.
+    Columns,
+}
+
+/// One problem on the worksheet: a block of columns with the operator underneath its leftmost column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    /// The columns the problem occupies on every line of the worksheet.
+    pub columns: Range<usize>,
+    pub operator: Operator,
+    /// The bytes of the input from the problem's top left corner to its bottom right.
+    pub span: Range<usize>,
+}
+
+/// Rows of numbers above a row of operators, with tabs expanded and short lines padded; columns count from there.
+#[derive(Clone, Debug)]
+pub struct Worksheet<'a> {
+    rows: Vec<Cow<'a, [u8]>>,
+    problems: Vec<Problem>,
+}
+
+pub const TAB_WIDTH: usize = 8;
+
+impl<'a> Worksheet<'a> {
+    pub fn parse(input: &'a str) -> Result<Self, ParseError> {
+        let mut offsets = Vec::new();
+        let mut raw = Vec::new();
+        let mut offset = 0;
+        for line in input.split_inclusive('\n') {
+            offsets.push(offset);
+            offset += line.len();
+            raw.push(line.trim_end_matches(['\n', '\r']));
+        }
+        while raw.last().is_some_and(|line| line.trim_ascii().is_empty()) {
+            raw.pop();
+        }
+        if raw.len() < 2 {
+            return Err(ParseError::TooFewLines);
+        }
+
+        let mut lines = raw.iter().map(|line| expand_tabs(line)).collect::<Vec<_>>();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        for line in &mut lines {
+            if line.len() < width {
+                line.to_mut().resize(width, b' ');
+            }
+        }
+
+        let operators = lines.pop().unwrap();
+        let operator_line = lines.len() + 1;
+        for (y, row) in lines.iter().enumerate() {
+            if let Some(x) = row.iter().position(|&b| b != b' ' && !b.is_ascii_digit()) {
+                let (column, found) = char_at(row, x);
+                return Err(ParseError::UnexpectedCharacter {
+                    line: y + 1,
+                    column,
+                    found,
+                });
+            }
+        }
+
+        let mut problems = Vec::new();
+        let mut start = 0;
+        for x in 0..=width {
+            if x < width && (operators[x] != b' ' || lines.iter().any(|line| line[x] != b' ')) {
+                continue;
+            }
+            if start < x {
+                let problem = Problem {
+                    columns: start..x,
+                    operator: parse_operator(&operators, start..x, operator_line)?,
+                    span: offsets[0] + byte_offset(raw[0], start)
+                        ..offsets[operator_line - 1] + byte_offset(raw[operator_line - 1], x),
+                };
+                for (y, row) in lines.iter().enumerate() {
+                    check_row_number(&row[problem.columns.clone()], y + 1, start)?;
+                }
+                problems.push(problem);
+            }
+            start = x + 1;
+        }
+
+        Ok(Self { rows: lines, problems })
+    }
+
+    pub fn problems(&self) -> &[Problem] {
+        &self.problems
+    }
+
+    /// Returns how many rows of numbers there are above the operators.
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the cells of the problem on row `y`, exactly as laid out on the worksheet.
+    pub fn row_cells(&self, y: usize, problem: &Problem) -> &str {
+        // Parsing made sure rows only contain digits and spaces.
+        std::str::from_utf8(&self.rows[y][problem.columns.clone()]).unwrap()
+    }
+
+    fn cell(&self, y: usize, x: usize) -> u8 {
+        self.rows[y][x]
+    }
+
+    /// Reads the problem's numbers off the worksheet, in the order they're operated on.
+    pub fn operands(&self, problem: &Problem, reading: Reading) -> Result<Vec<i128>, EvalErrorKind> {
+        match reading {
+            Reading::Rows => self.row_operands(problem).collect(),
+            Reading::Columns => self.column_operands(problem).collect(),
+        }
+    }
+
+    fn row_operands<'s>(&'s self, problem: &'s Problem) -> impl Iterator<Item = Result<i128, EvalErrorKind>> + 's {
+        self.rows.iter().map(|row| {
+            let cells = &row[problem.columns.clone()];
+            i128::from_radix_10_checked(cells.trim_ascii())
+                .0
+                .ok_or(EvalErrorKind::Overflow)
+        })
+    }
+
+    fn column_operands<'s>(&'s self, problem: &'s Problem) -> impl Iterator<Item = Result<i128, EvalErrorKind>> + 's {
+        problem.columns.clone().rev().map(|x| {
+            (0..self.rows.len())
+                .map(|y| self.cell(y, x))
+                .filter(u8::is_ascii_digit)
+                .try_fold(0i128, |n, digit| {
+                    n.checked_mul(10)?.checked_add(i128::from(digit - b'0'))
+                })
+                .ok_or(EvalErrorKind::Overflow)
+        })
+    }
+
+    /// Evaluates the `idx`-th problem on the worksheet.
+    pub fn evaluate(&self, idx: usize, reading: Reading) -> Result<i128, EvalError> {
+        let problem = &self.problems[idx];
+        self.operands(problem, reading)
+            .and_then(|operands| problem.operator.apply(operands))
+            .map_err(|kind| EvalError { problem: idx, kind })
+    }
+
+    /// Adds up the results of every problem, blaming an overflowing sum on the problem that pushed it over.
+    pub fn total(&self, reading: Reading) -> Result<i128, EvalError> {
+        (0..self.problems.len()).try_fold(0i128, |total, idx| {
+            total.checked_add(self.evaluate(idx, reading)?).ok_or(EvalError {
+                problem: idx,
+                kind: EvalErrorKind::Overflow,
+            })
+        })
+    }
+}
+
+/// Replaces every tab in `line` with enough spaces to reach the next tab stop, only allocating if there are any.
+fn expand_tabs(line: &str) -> Cow<'_, [u8]> {
+    if !line.contains('\t') {
+        return Cow::Borrowed(line.as_bytes());
+    }
+
+    let mut expanded = Vec::with_capacity(line.len());
+    for b in line.bytes() {
+        if b == b'\t' {
+            expanded.resize((expanded.len() + 1).next_multiple_of(TAB_WIDTH), b' ');
+        } else {
+            expanded.push(b);
+        }
+    }
+    Cow::Owned(expanded)
+}
+
+/// Returns the byte offset into `line` where the normalized `column` starts, or the end of the line if it's too short.
+fn byte_offset(line: &str, column: usize) -> usize {
+    let mut x = 0;
+    for (i, b) in line.bytes().enumerate() {
+        if x >= column {
+            return i;
+        }
+        x = if b == b'\t' {
+            (x + 1).next_multiple_of(TAB_WIDTH)
+        } else {
+            x + 1
+        };
+    }
+    line.len()
+}
+
+/// Parses the operator of the problem spanning `columns`, which must sit alone under the problem's leftmost column.
+fn parse_operator(operators: &[u8], columns: Range<usize>, line: usize) -> Result<Operator, ParseError> {
+    let start = columns.start;
+    let (column, glyph) = char_at(operators, start);
+    if let Some(x) = columns.skip(glyph.len_utf8()).find(|&x| operators[x] != b' ') {
+        let (column, found) = char_at(operators, x);
+        return Err(ParseError::UnexpectedCharacter { line, column, found });
+    }
+
+    match glyph {
+        ' ' => Err(ParseError::MissingOperator { line, column }),
+        _ => Operator::from_glyph(operators[start]).ok_or(ParseError::UnknownOperator { line, column, glyph }),
+    }
+}
+
+/// Returns the column, counting from one, and the character of a normalized line that byte `x` belongs to.
+fn char_at(line: &[u8], x: usize) -> (usize, char) {
+    // Normalizing only ever swaps ASCII for ASCII, so every line is still valid UTF-8.
+    let line = std::str::from_utf8(line).unwrap();
+    let start = (0..=x).rev().find(|&i| line.is_char_boundary(i)).unwrap();
+    (line[..start].chars().count() + 1, line[start..].chars().next().unwrap())
+}
+
+/// Checks that the cells of one row of a problem, starting at column `start`, hold exactly one number.
+fn check_row_number(cells: &[u8], line: usize, start: usize) -> Result<(), ParseError> {
+    let Some(first) = cells.iter().position(u8::is_ascii_digit) else {
+        return Err(ParseError::MissingNumber {
+            line,
+            column: start + 1,
+        });
+    };
+    let last = cells.iter().rposition(u8::is_ascii_digit).unwrap();
+    match cells[first..last].iter().position(|&b| b == b' ') {
+        Some(gap) => Err(ParseError::SplitNumber {
+            line,
+            column: start + first + gap + 1,
+        }),
+        None => Ok(()),
+    }
+}
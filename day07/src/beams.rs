@@ -0,0 +1,356 @@
+use std::{collections::VecDeque, error::Error, fmt};
+
+use grid::Grid;
+
+use crate::Timelines;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// The two directions at right angles to this one.
+    fn perpendicular(self) -> [Direction; 2] {
+        match self {
+            Direction::Up | Direction::Down => [Direction::Left, Direction::Right],
+            Direction::Left | Direction::Right => [Direction::Up, Direction::Down],
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+}
+
+/// A side of the manifold, through which beams can leave it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    /// `.`, or `S` where the beam starts.
+    Empty,
+    /// `/`, turning beams moving right to go up and so on.
+    ForwardMirror,
+    /// `\`, turning beams moving right to go down and so on.
+    BackMirror,
+    /// `^`, `v`, `<` or `>`, splitting beams moving against it into the cells on either side.
+    OneWaySplitter(Direction),
+    /// `|` or `-`, splitting beams moving across it both ways along it.
+    Splitter { vertical: bool },
+    /// `#`, stopping any beam that enters it.
+    Absorber,
+}
+
+impl Tile {
+    pub fn from_glyph(glyph: u8) -> Option<Self> {
+        Some(match glyph {
+            b'.' | b'S' => Tile::Empty,
+            b'/' => Tile::ForwardMirror,
+            b'\\' => Tile::BackMirror,
+            b'^' => Tile::OneWaySplitter(Direction::Down),
+            b'v' => Tile::OneWaySplitter(Direction::Up),
+            b'<' => Tile::OneWaySplitter(Direction::Right),
+            b'>' => Tile::OneWaySplitter(Direction::Left),
+            b'|' => Tile::Splitter { vertical: true },
+            b'-' => Tile::Splitter { vertical: false },
+            b'#' => Tile::Absorber,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// Lines and columns count from one.
+    UnknownTile { line: usize, column: usize, found: char },
+    /// Every line of the manifold must be as long as the first one.
+    RaggedLine { line: usize },
+    /// There's no `S` for the beam to start from.
+    MissingStart,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownTile { line, column, found } => {
+                write!(f, "line {line}, column {column}: unknown tile {found:?}")
+            }
+            ParseError::RaggedLine { line } => write!(f, "line {line}: expected the same length as the first line"),
+            ParseError::MissingStart => write!(f, "no `S` for the beam to start from"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Beams leaving the manifold through one cell of one of its sides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Exit {
+    pub side: Side,
+    /// The column for the top and bottom sides, or the row for the left and right ones.
+    pub index: usize,
+    /// How many timelines leave through here, or `None` if beams going round in a loop make that unbounded.
+    pub timelines: Option<Timelines>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Report {
+    /// Every cell a beam entered, including the one it started in.
+    pub energized: Grid<bool>,
+    /// How many distinct splitters split at least one beam.
+    pub splits: usize,
+    /// Every place beams left the manifold, ordered by side and then index.
+    pub exits: Vec<Exit>,
+    /// Whether any beam ended up going round in a loop.
+    pub looped: bool,
+}
+
+impl Report {
+    pub fn energized_count(&self) -> usize {
+        self.energized.iter().filter(|&&cell| cell).count()
+    }
+
+    /// Adds up the timelines leaving through `side`, or `None` if any of them are unbounded.
+    pub fn timelines_through(&self, side: Side) -> Option<Timelines> {
+        self.exits
+            .iter()
+            .filter(|exit| exit.side == side)
+            .try_fold(Timelines::default(), |mut total, exit| {
+                total += exit.timelines.as_ref()?;
+                Some(total)
+            })
+    }
+}
+
+/// Reads a manifold into a grid, turning every glyph into a cell with `cell`.
+pub(crate) fn read_grid<T>(input: &str, mut cell: impl FnMut(u8) -> Option<T>) -> Result<Grid<T>, ParseError> {
+    let width = input.lines().next().map_or(0, str::len);
+    let mut cells = Vec::new();
+
+    for (y, line) in input.lines().enumerate() {
+        if line.len() != width {
+            return Err(ParseError::RaggedLine { line: y + 1 });
+        }
+        for (x, glyph) in line.bytes().enumerate() {
+            cells.push(cell(glyph).ok_or(ParseError::UnknownTile {
+                line: y + 1,
+                column: x + 1,
+                found: char::from(glyph),
+            })?);
+        }
+    }
+
+    Ok(Grid::from_vec(cells, width))
+}
+
+/// Returns the position of the first `S` in a manifold, if there is one.
+pub(crate) fn find_start(input: &str) -> Option<(usize, usize)> {
+    input
+        .lines()
+        .enumerate()
+        .find_map(|(y, line)| line.find('S').map(|x| (y, x)))
+}
+
+/// Where a beam goes after one step: into another cell of the manifold, or out of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Step {
+    Beam((usize, usize), Direction),
+    Exit(Side, usize),
+}
+
+/// A manifold of arbitrary tiles, with beams travelling in any direction.
+#[derive(Clone, Debug)]
+pub struct Manifold {
+    tiles: Grid<Tile>,
+    start: Option<(usize, usize)>,
+}
+
+impl Manifold {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            tiles: read_grid(input, Tile::from_glyph)?,
+            start: find_start(input),
+        })
+    }
+
+    /// Returns the position of the first `S` on the map, if there is one.
+    pub fn start(&self) -> Option<(usize, usize)> {
+        self.start
+    }
+
+    fn offset(&self, (y, x): (usize, usize), direction: Direction) -> Result<(usize, usize), (Side, usize)> {
+        match direction {
+            Direction::Up if y == 0 => Err((Side::Top, x)),
+            Direction::Up => Ok((y - 1, x)),
+            Direction::Down if y + 1 == self.tiles.rows() => Err((Side::Bottom, x)),
+            Direction::Down => Ok((y + 1, x)),
+            Direction::Left if x == 0 => Err((Side::Left, y)),
+            Direction::Left => Ok((y, x - 1)),
+            Direction::Right if x + 1 == self.tiles.cols() => Err((Side::Right, y)),
+            Direction::Right => Ok((y, x + 1)),
+        }
+    }
+
+    /// Places a beam moving in `direction` one cell over from `pos` towards `towards`.
+    fn beside(&self, pos: (usize, usize), towards: Direction, direction: Direction) -> Step {
+        match self.offset(pos, towards) {
+            Ok(pos) => Step::Beam(pos, direction),
+            Err((side, index)) => Step::Exit(side, index),
+        }
+    }
+
+    /// Moves a beam at `pos` one cell in `direction`, returning where it ends up and whether it got split.
+    fn step(&self, pos: (usize, usize), direction: Direction) -> ([Option<Step>; 2], Option<(usize, usize)>) {
+        let next = match self.offset(pos, direction) {
+            Ok(next) => next,
+            Err((side, index)) => return ([Some(Step::Exit(side, index)), None], None),
+        };
+
+        let reflect = |turn: [Direction; 4]| Step::Beam(next, turn[direction as usize]);
+        match self.tiles[next] {
+            Tile::Empty => ([Some(Step::Beam(next, direction)), None], None),
+            Tile::OneWaySplitter(splits) if splits != direction => ([Some(Step::Beam(next, direction)), None], None),
+            Tile::Splitter { vertical } if vertical == direction.is_vertical() => {
+                ([Some(Step::Beam(next, direction)), None], None)
+            }
+            // Indexed by the incoming direction, in declaration order.
+            Tile::ForwardMirror => {
+                let turn = [Direction::Right, Direction::Left, Direction::Down, Direction::Up];
+                ([Some(reflect(turn)), None], None)
+            }
+            Tile::BackMirror => {
+                let turn = [Direction::Left, Direction::Right, Direction::Up, Direction::Down];
+                ([Some(reflect(turn)), None], None)
+            }
+            Tile::OneWaySplitter(_) => {
+                let [a, b] = direction.perpendicular();
+                let steps = [self.beside(next, a, direction), self.beside(next, b, direction)];
+                (steps.map(Some), Some(next))
+            }
+            Tile::Splitter { .. } => {
+                let [a, b] = direction.perpendicular();
+                ([Some(Step::Beam(next, a)), Some(Step::Beam(next, b))], Some(next))
+            }
+            Tile::Absorber => ([None, None], None),
+        }
+    }
+
+    /// Follows a beam from `start` until everything it splits into has left, been absorbed, or started looping.
+    pub fn simulate(&self, start: (usize, usize), direction: Direction) -> Report {
+        let (rows, cols) = (self.tiles.rows(), self.tiles.cols());
+        let state_id = |(y, x): (usize, usize), direction: Direction| (y * cols + x) * 4 + direction as usize;
+
+        let mut energized = Grid::new(rows, cols);
+        let mut split = Grid::new(rows, cols);
+        energized[start] = true;
+
+        // Find every reachable state, numbering them in the order they're found.
+        let mut index = vec![u32::MAX; rows * cols * 4];
+        let mut states = vec![(start, direction)];
+        let mut edges = Vec::new();
+        index[state_id(start, direction)] = 0;
+
+        let mut i = 0;
+        while let Some(&(pos, direction)) = states.get(i) {
+            // The cell a beam moves into counts as energized even if it absorbs the beam or splits it off to the sides.
+            if let Ok(next) = self.offset(pos, direction) {
+                energized[next] = true;
+            }
+            let (steps, splitter) = self.step(pos, direction);
+            if let Some(splitter) = splitter {
+                split[splitter] = true;
+            }
+
+            let mut out = [None; 2];
+            for (slot, step) in out.iter_mut().zip(steps) {
+                *slot = step.map(|step| match step {
+                    Step::Beam(pos, direction) => {
+                        energized[pos] = true;
+                        let id = &mut index[state_id(pos, direction)];
+                        if *id == u32::MAX {
+                            *id = states.len() as u32;
+                            states.push((pos, direction));
+                        }
+                        Ok(*id as usize)
+                    }
+                    Step::Exit(side, index) => Err((side, index)),
+                });
+            }
+            edges.push(out);
+            i += 1;
+        }
+
+        // Count paths in topological order. Anything that never becomes ready is on a loop or downstream of one.
+        let mut in_degree = vec![0usize; states.len()];
+        for &successor in edges.iter().flatten().flatten() {
+            if let Ok(successor) = successor {
+                in_degree[successor] += 1;
+            }
+        }
+
+        let mut timelines = vec![Timelines::default(); states.len()];
+        let mut done = vec![false; states.len()];
+        let mut exits = Vec::<((Side, usize), Option<Timelines>)>::new();
+        let mut exit = |key, n: Option<&Timelines>| {
+            let pos = match exits.iter().position(|(k, _)| *k == key) {
+                Some(pos) => pos,
+                None => {
+                    exits.push((key, Some(Timelines::default())));
+                    exits.len() - 1
+                }
+            };
+            let total = &mut exits[pos].1;
+            match (total.as_mut(), n) {
+                (Some(total), Some(n)) => *total += n,
+                _ => *total = None,
+            }
+        };
+
+        timelines[0] = Timelines::from(1);
+        let mut queue = VecDeque::from_iter((in_degree[0] == 0).then_some(0));
+        while let Some(state) = queue.pop_front() {
+            done[state] = true;
+            for successor in edges[state].into_iter().flatten() {
+                match successor {
+                    Ok(successor) => {
+                        let n = timelines[state].clone();
+                        timelines[successor] += &n;
+                        in_degree[successor] -= 1;
+                        if in_degree[successor] == 0 {
+                            queue.push_back(successor);
+                        }
+                    }
+                    Err(key) => exit(key, Some(&timelines[state])),
+                }
+            }
+        }
+
+        let looped = done.contains(&false);
+        for state in (0..states.len()).filter(|&state| !done[state]) {
+            for key in edges[state].into_iter().flatten().filter_map(Result::err) {
+                exit(key, None);
+            }
+        }
+
+        exits.sort_unstable_by_key(|&(key, _)| key);
+        Report {
+            energized,
+            splits: split.iter().filter(|&&hit| hit).count(),
+            exits: exits
+                .into_iter()
+                .map(|((side, index), timelines)| Exit { side, index, timelines })
+                .collect(),
+            looped,
+        }
+    }
+}
@@ -1,79 +1,53 @@
 use std::{fmt::Display, mem::swap};
 
-use fixedbitset::FixedBitSet;
 use grid::Grid;
 
+mod beams;
+mod render;
+mod timelines;
+
+pub use beams::{Direction, Exit, Manifold, ParseError, Report, Side, Tile};
+pub use render::Trace;
+pub use timelines::Timelines;
+
 #[inline]
 pub fn solve() -> (impl Display, impl Display) {
-    // Assumption: odd-indexed rows (i.e. 1, 3, 5, ...) are empty
-    let input = include_str!("input.txt");
-    let width = input.lines().next().unwrap().len();
-    let map = Grid::from_vec(input.lines().step_by(2).flat_map(|l| l.bytes()).collect(), width);
-    let start = map.indexed_iter().find(|(_pos, cell)| **cell == b'S').unwrap().0;
+    let (map, start) = parse(include_str!("input.txt")).unwrap();
+    simulate(&map, start, |_, _| {})
+}
 
-    (solve_part1(&map, start), solve_part2(&map, start))
+/// Reads a manifold of only `.`, `S` and `^` into a grid of its glyphs, along with where its beam starts.
+fn parse(input: &str) -> Result<(Grid<u8>, (usize, usize)), ParseError> {
+    let map = beams::read_grid(input, |glyph| matches!(glyph, b'.' | b'S' | b'^').then_some(glyph))?;
+    Ok((map, beams::find_start(input).ok_or(ParseError::MissingStart)?))
 }
 
-fn solve_part1(map: &Grid<u8>, start: (usize, usize)) -> i32 {
-    debug_assert_eq!(start.0, 0);
-    let mut part1 = 0;
-    let mut xs = FixedBitSet::with_capacity(map.cols());
-    let mut new_xs = FixedBitSet::with_capacity(map.cols());
-    xs.insert(start.1);
-    for y in 0..map.rows() - 1 {
-        let next_y = y + 1;
-        for x in xs.ones() {
-            if map[(next_y, x)] == b'^' {
-                part1 += 1;
+/// Sweeps the beams down row by row, passing each to `visit`; returns the splitters hit and the timelines left.
+fn simulate(map: &Grid<u8>, start: (usize, usize), mut visit: impl FnMut(usize, &[Timelines])) -> (usize, Timelines) {
+    let mut splits = 0;
+    let mut timelines = vec![Timelines::default(); map.cols()];
+    let mut new_timelines = vec![Timelines::default(); map.cols()];
+    timelines[start.1] = Timelines::from(1);
+    visit(start.0, &timelines);
+
+    for y in start.0 + 1..map.rows() {
+        for (x, n) in timelines.iter().enumerate().filter(|(_, n)| !n.is_zero()) {
+            if map[(y, x)] == b'^' {
+                splits += 1;
                 if x != 0 {
-                    new_xs.insert(x - 1);
+                    new_timelines[x - 1] += n;
                 }
                 if x != map.cols() - 1 {
-                    new_xs.insert(x + 1);
+                    new_timelines[x + 1] += n;
                 }
             } else {
-                new_xs.insert(x);
+                new_timelines[x] += n;
             }
         }
-        xs.clear();
-        swap(&mut xs, &mut new_xs);
-    }
-    part1
-}
-
-fn solve_part2(map: &Grid<u8>, pos: (usize, usize)) -> usize {
-    let mut memo: Grid<Option<usize>> = Grid::new(map.rows(), map.cols());
-    solve_part2_rec(map, pos, &mut memo)
-}
-
-fn solve_part2_rec(map: &Grid<u8>, pos: (usize, usize), memo: &mut Grid<Option<usize>>) -> usize {
-    if let Some(cached_result) = memo[pos] {
-        return cached_result;
+        swap(&mut timelines, &mut new_timelines);
+        new_timelines.fill(Timelines::default());
+        visit(y, &timelines);
     }
 
-    let (y, x) = pos;
-    let new_y = y + 1;
-
-    let result = if new_y == map.rows() {
-        1
-    } else if map[(new_y, x)] == b'^' {
-        let left = if x != 0 {
-            solve_part2_rec(map, (y, x - 1), memo)
-        } else {
-            0
-        };
-
-        let right = if x != map.cols() - 1 {
-            solve_part2_rec(map, (y, x + 1), memo)
-        } else {
-            0
-        };
-
-        left + right
-    } else {
-        solve_part2_rec(map, (new_y, x), memo)
-    };
-
-    memo[pos] = Some(result);
-    result
+    (splits, timelines.iter().sum())
 }
@@ -0,0 +1,143 @@
+use std::fmt::Write;
+
+use grid::Grid;
+
+use crate::{ParseError, Timelines, parse, simulate};
+
+/// Characters for cells with beams in them, from the fewest timelines passing through to the most.
+const SHADES: &[u8] = b":;+=*%#@";
+
+/// Size of a cell in SVG pixels.
+const CELL: usize = 10;
+
+/// The beams traced through a manifold: how many timelines pass through every cell, and which splitters get hit.
+#[derive(Clone, Debug)]
+pub struct Trace {
+    map: Grid<u8>,
+    timelines: Grid<Timelines>,
+    /// Bits in the largest number of timelines in any one cell, which is what gets the brightest shade.
+    max_bits: u64,
+}
+
+impl Trace {
+    /// Traces the beam through the manifold in `input`, which may only hold `.`, `S` and `^`.
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let (map, start) = parse(input)?;
+        let mut timelines = Grid::<Timelines>::new(map.rows(), map.cols());
+        simulate(&map, start, |y, row| {
+            for (cell, n) in timelines.iter_row_mut(y).zip(row) {
+                cell.clone_from(n);
+            }
+        });
+        let max_bits = timelines.iter().map(Timelines::bits).max().unwrap_or(0);
+
+        Ok(Self {
+            map,
+            timelines,
+            max_bits,
+        })
+    }
+
+    /// Returns how many timelines have a beam at `pos`, the same counts part 2 adds up along the bottom row.
+    pub fn timelines(&self, pos: (usize, usize)) -> &Timelines {
+        &self.timelines[pos]
+    }
+
+    /// Returns whether there's a splitter at `pos` that a beam runs into, which is what part 1 counts.
+    pub fn is_hit(&self, (y, x): (usize, usize)) -> bool {
+        self.map[(y, x)] == b'^' && y > 0 && !self.timelines[(y - 1, x)].is_zero()
+    }
+
+    /// Returns how brightly to shade a cell with `n` timelines, from 0 to 1 on a logarithmic scale.
+    fn shade(&self, n: &Timelines) -> f64 {
+        if self.max_bits <= 1 {
+            return 1.0;
+        }
+        (n.bits() - 1) as f64 / (self.max_bits - 1) as f64
+    }
+
+    /// Draws the manifold as text, with beams in `:;+=*%#@` and splitters no beam reaches as `_`.
+    pub fn render_ascii(&self) -> String {
+        let mut out = String::with_capacity((self.map.cols() + 1) * self.map.rows());
+        for y in 0..self.map.rows() {
+            for x in 0..self.map.cols() {
+                let n = &self.timelines[(y, x)];
+                out.push(match self.map[(y, x)] {
+                    b'^' if self.is_hit((y, x)) => '^',
+                    b'^' => '_',
+                    b'S' => 'S',
+                    glyph if n.is_zero() => char::from(glyph),
+                    _ => {
+                        let level = (self.shade(n) * (SHADES.len() - 1) as f64).round() as usize;
+                        char::from(SHADES[level])
+                    }
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Draws the manifold as a standalone SVG image, with each beam's timeline count as a tooltip.
+    pub fn render_svg(&self) -> String {
+        let (width, height) = (self.map.cols() * CELL, self.map.rows() * CELL);
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+        )
+        .unwrap();
+        writeln!(out, r##"<rect width="100%" height="100%" fill="#101820"/>"##).unwrap();
+
+        for ((y, x), &glyph) in self.map.indexed_iter() {
+            let (left, top) = (x * CELL, y * CELL);
+            let n = &self.timelines[(y, x)];
+            match glyph {
+                b'^' => {
+                    let (fill, stroke, title) = if self.is_hit((y, x)) {
+                        ("#ff8c1a", "#ff8c1a", "hit")
+                    } else {
+                        ("none", "#6b7280", "not hit")
+                    };
+                    writeln!(
+                        out,
+                        r#"<polygon points="{},{} {},{} {},{}" fill="{fill}" stroke="{stroke}"><title>splitter, {title}</title></polygon>"#,
+                        left + CELL / 2,
+                        top + 1,
+                        left + CELL - 1,
+                        top + CELL - 1,
+                        left + 1,
+                        top + CELL - 1,
+                    )
+                    .unwrap();
+                }
+                b'S' => writeln!(
+                    out,
+                    r##"<circle cx="{}" cy="{}" r="{}" fill="#ffffff"><title>start</title></circle>"##,
+                    left + CELL / 2,
+                    top + CELL / 2,
+                    CELL / 2 - 1,
+                )
+                .unwrap(),
+                _ if !n.is_zero() => {
+                    let (r, g, b) = blend((31, 59, 115), (255, 224, 102), self.shade(n));
+                    writeln!(
+                        out,
+                        r#"<rect x="{left}" y="{top}" width="{CELL}" height="{CELL}" fill="rgb({r},{g},{b})"><title>timelines: {n}</title></rect>"#
+                    )
+                    .unwrap();
+                }
+                _ => {}
+            }
+        }
+
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+/// Mixes two colours, going from `from` at 0 to `to` at 1.
+fn blend(from: (u8, u8, u8), to: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let mix = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
@@ -0,0 +1,77 @@
+use std::{fmt, iter::Sum, ops::AddAssign};
+
+use num_bigint::BigUint;
+
+/// A number of timelines: a plain `u128` for as long as that's big enough, and a `BigUint` from then on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Timelines {
+    Small(u128),
+    Big(BigUint),
+}
+
+impl Timelines {
+    pub fn is_zero(&self) -> bool {
+        matches!(self, Timelines::Small(0))
+    }
+
+    /// Returns how many bits it takes to write the number down, which is zero for zero.
+    pub fn bits(&self) -> u64 {
+        match self {
+            Timelines::Small(n) => u64::from(u128::BITS - n.leading_zeros()),
+            Timelines::Big(n) => n.bits(),
+        }
+    }
+}
+
+impl Default for Timelines {
+    fn default() -> Self {
+        Timelines::Small(0)
+    }
+}
+
+impl From<u128> for Timelines {
+    fn from(n: u128) -> Self {
+        Timelines::Small(n)
+    }
+}
+
+impl From<Timelines> for BigUint {
+    fn from(n: Timelines) -> Self {
+        match n {
+            Timelines::Small(n) => BigUint::from(n),
+            Timelines::Big(n) => n,
+        }
+    }
+}
+
+impl AddAssign<&Timelines> for Timelines {
+    fn add_assign(&mut self, rhs: &Timelines) {
+        match (&mut *self, rhs) {
+            (Timelines::Small(a), Timelines::Small(b)) => match a.checked_add(*b) {
+                Some(sum) => *a = sum,
+                None => *self = Timelines::Big(BigUint::from(*a) + *b),
+            },
+            (Timelines::Big(a), Timelines::Small(b)) => *a += *b,
+            (Timelines::Big(a), Timelines::Big(b)) => *a += b,
+            (Timelines::Small(a), Timelines::Big(b)) => *self = Timelines::Big(b + *a),
+        }
+    }
+}
+
+impl<'a> Sum<&'a Timelines> for Timelines {
+    fn sum<I: Iterator<Item = &'a Timelines>>(iter: I) -> Self {
+        iter.fold(Timelines::default(), |mut acc, n| {
+            acc += n;
+            acc
+        })
+    }
+}
+
+impl fmt::Display for Timelines {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Timelines::Small(n) => n.fmt(f),
+            Timelines::Big(n) => n.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use union_find::*;
+
+use crate::{Metric, Mst, SpatialIndex, TooFarApart};
+
+/// What the circuits look like after connecting the closest pairs of junction boxes one at a time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Circuits {
+    /// The size of every circuit after the requested number of connections, largest first.
+    pub sizes: Vec<usize>,
+    /// The product of the sizes of the `k` largest circuits, or `None` if it doesn't fit in a `usize`.
+    pub largest_product: Option<usize>,
+    /// The connection that put every box in a single circuit, or `None` with fewer than two boxes.
+    pub completion: Option<Completion>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Completion {
+    /// How many connections it took, including ones between boxes already in the same circuit.
+    pub step: usize,
+    /// The indices of the two boxes connected last, lowest first.
+    pub pair: (usize, usize),
+}
+
+/// Connects the closest pairs of `boxes` until they form one circuit, reporting the circuits after `connections`.
+pub fn connect<const D: usize>(
+    boxes: &[[i64; D]],
+    metric: impl Metric,
+    connections: usize,
+    k: usize,
+    mst: Mst,
+) -> Result<Circuits, TooFarApart> {
+    let index = SpatialIndex::new(boxes, metric)?;
+    let mut circuits = QuickUnionUf::<UnionBySize>::new(boxes.len());
+    let mut sizes = None;
+    let mut completion = None;
+    let mut merges = 0;
+
+    // A single box is connected from the start, without any connection to report.
+    if boxes.len() > 1 {
+        for (connections_made, pair) in index.edges().enumerate() {
+            if connections_made == connections {
+                sizes = Some(circuit_sizes(&mut circuits, boxes.len()));
+                // Kruskal's algorithm finds the last connection by carrying on, while the others find it on their own.
+                if mst != Mst::Kruskal {
+                    break;
+                }
+            }
+
+            // ↓ Returns true if the two elements were in different sets
+            if circuits.union(pair.1 as usize, pair.2 as usize) {
+                merges += 1;
+            }
+
+            if merges == boxes.len() - 1 {
+                completion = Some(Completion {
+                    step: connections_made + 1,
+                    pair: (pair.1 as usize, pair.2 as usize),
+                });
+                break;
+            }
+        }
+
+        if completion.is_none() && mst != Mst::Kruskal {
+            let last = *index.spanning_tree(mst).last().unwrap();
+            completion = Some(Completion {
+                step: index.rank(last),
+                pair: (last.1 as usize, last.2 as usize),
+            });
+        }
+    }
+
+    let sizes = sizes.unwrap_or_else(|| circuit_sizes(&mut circuits, boxes.len()));
+    Ok(Circuits {
+        largest_product: sizes
+            .iter()
+            .take(k)
+            .try_fold(1usize, |product, &size| product.checked_mul(size)),
+        sizes,
+        completion,
+    })
+}
+
+fn circuit_sizes(circuits: &mut QuickUnionUf<UnionBySize>, len: usize) -> Vec<usize> {
+    let mut size = vec![0usize; len];
+    for i in 0..len {
+        size[circuits.find(i)] += 1;
+    }
+    size.retain(|&n| n > 0);
+    size.sort_unstable_by(|a, b| b.cmp(a));
+    size
+}
@@ -0,0 +1,150 @@
+use std::fmt::Write;
+
+use union_find::*;
+
+use crate::{Metric, Mst, SpatialIndex, TooFarApart};
+
+/// One step of single-linkage clustering: two clusters joined by the shortest connection between them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Merge {
+    /// The clusters merged, numbered like a SciPy linkage matrix: boxes are `0..n` and merge `i` forms `n + i`.
+    pub clusters: (usize, usize),
+    /// The indices of the two boxes whose connection merged the clusters, lowest first.
+    pub pair: (usize, usize),
+    pub distance: u64,
+    pub size: usize,
+}
+
+/// The order in which connecting the closest pairs of boxes merges them, as a single-linkage clustering.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dendrogram {
+    len: usize,
+    merges: Vec<Merge>,
+}
+
+impl Dendrogram {
+    pub fn new<const D: usize>(boxes: &[[i64; D]], metric: impl Metric, mst: Mst) -> Result<Self, TooFarApart> {
+        let index = SpatialIndex::new(boxes, metric)?;
+        let mut circuits = QuickUnionUf::<UnionBySize>::new(boxes.len());
+        // The cluster each circuit currently stands for, indexed by its root.
+        let mut cluster = (0..boxes.len()).collect::<Vec<_>>();
+        let mut size = vec![1; boxes.len()];
+        let mut merges = Vec::with_capacity(boxes.len().saturating_sub(1));
+
+        for (distance, i, j) in index.spanning_tree(mst) {
+            let (i, j) = (i as usize, j as usize);
+            let (a, b) = (circuits.find(i), circuits.find(j));
+            circuits.union(a, b);
+
+            let root = circuits.find(a);
+            merges.push(Merge {
+                clusters: (cluster[a], cluster[b]),
+                pair: (i, j),
+                distance,
+                size: size[a] + size[b],
+            });
+            cluster[root] = boxes.len() + merges.len() - 1;
+            size[root] = size[a] + size[b];
+        }
+
+        Ok(Self {
+            len: boxes.len(),
+            merges,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns every merge in increasing order of distance.
+    pub fn merges(&self) -> &[Merge] {
+        &self.merges
+    }
+
+    fn height(&self, cluster: usize) -> u64 {
+        cluster.checked_sub(self.len).map_or(0, |i| self.merges[i].distance)
+    }
+
+    /// Writes the clustering in Newick format, with boxes labelled by index and merges at the height of their distance.
+    pub fn newick(&self) -> String {
+        enum Step {
+            Cluster(usize, Option<u64>),
+            Comma,
+            Close(Option<u64>),
+        }
+
+        let mut out = String::new();
+        let Some(root) = (self.len + self.merges.len()).checked_sub(1) else {
+            out.push(';');
+            return out;
+        };
+
+        // Clusters can be nested as deep as there are boxes, so walk the tree without recursing.
+        let mut stack = vec![Step::Cluster(root, None)];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Cluster(cluster, parent) => {
+                    let length = parent.map(|height| height - self.height(cluster));
+                    if cluster < self.len {
+                        write!(out, "{cluster}").unwrap();
+                        if let Some(length) = length {
+                            write!(out, ":{length}").unwrap();
+                        }
+                    } else {
+                        let (a, b) = self.merges[cluster - self.len].clusters;
+                        let height = Some(self.height(cluster));
+                        out.push('(');
+                        stack.extend([
+                            Step::Close(length),
+                            Step::Cluster(b, height),
+                            Step::Comma,
+                            Step::Cluster(a, height),
+                        ]);
+                    }
+                }
+                Step::Comma => out.push(','),
+                Step::Close(length) => {
+                    out.push(')');
+                    if let Some(length) = length {
+                        write!(out, ":{length}").unwrap();
+                    }
+                }
+            }
+        }
+
+        out.push(';');
+        out
+    }
+
+    /// Returns the cluster of every box after the merges at most `distance` long, numbered by their lowest box.
+    pub fn cut(&self, distance: u64) -> Vec<usize> {
+        let mut clusters = QuickUnionUf::<UnionBySize>::new(self.len + self.merges.len());
+        for (i, merge) in self
+            .merges
+            .iter()
+            .take_while(|merge| merge.distance <= distance)
+            .enumerate()
+        {
+            clusters.union(merge.clusters.0, self.len + i);
+            clusters.union(merge.clusters.1, self.len + i);
+        }
+
+        let mut label = vec![usize::MAX; self.len + self.merges.len()];
+        let mut next = 0;
+        (0..self.len)
+            .map(|i| {
+                let root = clusters.find(i);
+                if label[root] == usize::MAX {
+                    label[root] = next;
+                    next += 1;
+                }
+                label[root]
+            })
+            .collect()
+    }
+}
@@ -1,92 +1,32 @@
 use std::fmt::Display;
 
-use atoi::FromRadix10;
-use itertools::Itertools;
-use rayon::prelude::*;
-use union_find::*;
-use wide::u64x4;
-
-/// Assumption: the input is well-behaved enough that we can "draw the line" at a certain distance
-/// and still get the correct answer; based on empirical data, there are around half a million edges
-/// and only at most ten thousand of them are relevant to the MST.
-///
-/// If you care about absolute correctness, you can replace the `unreachable!()` at the end of
-/// `solve` with an implementation that handles the remaining edges.
-const CUTOFF_DISTANCE_SQUARE: u64 = 256_000_000;
+mod circuits;
+mod dendrogram;
+mod metric;
+mod mst;
+mod parse;
+mod spatial;
+
+pub use circuits::{Circuits, Completion, connect};
+pub use dendrogram::{Dendrogram, Merge};
+pub use metric::{Chebyshev, Manhattan, Metric, SquaredEuclidean};
+pub use mst::Mst;
+pub use parse::{ParseError, parse_boxes};
+pub use spatial::{Edges, SpatialIndex, TooFarApart};
 
 #[inline]
 pub fn solve() -> (impl Display, impl Display) {
-    let input = include_str!("input.txt");
-    let boxes = input
-        .lines()
-        .map(|line| -> (u64, u64, u64) {
-            line.split(',')
-                .map(|n| u64::from_radix_10(n.as_bytes()).0)
-                .collect_tuple()
-                .unwrap()
-        })
-        .collect_vec();
-    let boxes = &boxes;
-
-    let mut edges = (0..boxes.len())
-        .into_par_iter()
-        .flat_map_iter(|i| {
-            ((i + 1)..boxes.len()).filter_map(move |j| {
-                let d = dist(boxes[i], boxes[j]);
-                (d <= CUTOFF_DISTANCE_SQUARE).then_some((d, i as u16, j as u16))
-            })
-        })
-        .collect::<Vec<_>>();
-    edges.par_sort_unstable_by_key(|&(d, ..)| d);
-
-    let mut circuits = QuickUnionUf::<UnionBySize>::new(boxes.len());
-    let mut part1 = 0;
-    let mut merges = 0;
-    let mut connections_made = 0;
-    for pair in edges {
-        if connections_made == 1000 {
-            let mut size = vec![0usize; boxes.len()];
-            for i in 0..boxes.len() {
-                size[circuits.find(i)] += 1;
-            }
-            size.sort_unstable();
-            size.reverse();
-            part1 = size[0] * size[1] * size[2];
-        }
-        connections_made += 1;
-
-        // ↓ Returns true if the two elements were in different sets
-        if circuits.union(pair.1 as usize, pair.2 as usize) {
-            merges += 1;
-        }
-
-        if merges == boxes.len() - 1 {
-            let part2 = boxes[pair.1 as usize].0 * boxes[pair.2 as usize].0;
-            return (part1, part2);
-        }
-    }
-
-    unreachable!()
+    solve_with(Mst::default())
 }
 
-#[inline(always)]
-fn dist(a: (u64, u64, u64), b: (u64, u64, u64)) -> u64 {
-    // 1. Convert tuples to SIMD vectors (Structure of Arrays)
-    // We pad the 4th lane with 0.
-    let a_vec = u64x4::from([a.0, a.1, a.2, 0]);
-    let b_vec = u64x4::from([b.0, b.1, b.2, 0]);
-
-    // 2. Compute difference
-    // Note: SIMD integer subtraction is usually wrapping.
-    // In modular arithmetic, (a - b)^2 is mathematically valid
-    // even if (a - b) wraps around, so we don't need explicit abs_diff logic.
-    let diff = a_vec - b_vec;
-
-    // 3. Square (Multiplication)
-    let squared = diff * diff;
-
-    // 4. Horizontal Sum
-    // We extract the array and sum it up to get the scalar result.
-    let arr: [u64; 4] = squared.into();
-    arr.into_iter().sum()
+/// Solves both parts, finding the connection that completes the circuit with `mst`.
+#[inline]
+pub fn solve_with(mst: Mst) -> (impl Display, impl Display) {
+    let boxes = parse_boxes::<3>(include_str!("input.txt"), SquaredEuclidean).unwrap();
+    let circuits = connect(&boxes, SquaredEuclidean, 1000, 3, mst).unwrap();
+    let (i, j) = circuits.completion.expect("need at least two junction boxes").pair;
+    (
+        circuits.largest_product.expect("product of circuit sizes too large"),
+        i128::from(boxes[i][0]) * i128::from(boxes[j][0]),
+    )
 }
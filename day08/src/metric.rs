@@ -0,0 +1,102 @@
+use wide::u64x4;
+
+/// A distance built from the distances along each axis, which must never shrink as any one of those grows.
+pub trait Metric: Copy + Send + Sync {
+    /// Combines the distances along up to four axes, padded with zeros, wrapping on overflow.
+    fn combine_lanes(self, diffs: [u64; 4]) -> u64;
+
+    /// Combines the distances along each axis, or returns `None` if the result doesn't fit in a `u64`.
+    fn checked_combine(self, diffs: impl IntoIterator<Item = u64>) -> Option<u64>;
+
+    /// Combines the distances along each axis, saturating at `u64::MAX`.
+    fn combine(self, diffs: impl IntoIterator<Item = u64>) -> u64 {
+        self.checked_combine(diffs).unwrap_or(u64::MAX)
+    }
+
+    /// Returns how far apart along any one axis two points can be if they're at most `distance` apart.
+    fn reach(self, distance: u64) -> u64;
+
+    /// Returns how far apart `a` and `b` are, wrapping on overflow.
+    #[inline(always)]
+    fn distance<const D: usize>(self, a: &[i64; D], b: &[i64; D]) -> u64 {
+        if D <= 4 {
+            // Flipping the sign bit maps `i64` onto `u64` keeping order and differences.
+            let (mut a_lanes, mut b_lanes) = ([0; 4], [0; 4]);
+            for (lane, &x) in a_lanes.iter_mut().zip(a).chain(b_lanes.iter_mut().zip(b)) {
+                *lane = x.cast_unsigned() ^ (1 << 63);
+            }
+            let (a, b) = (u64x4::from(a_lanes), u64x4::from(b_lanes));
+            self.combine_lanes((a.max(b) - a.min(b)).into())
+        } else {
+            self.combine(a.iter().zip(b).map(|(&a, &b)| a.abs_diff(b)))
+        }
+    }
+
+    /// Returns how far apart `a` and `b` are, or `None` if that doesn't fit in a `u64`.
+    fn checked_distance<const D: usize>(self, a: &[i64; D], b: &[i64; D]) -> Option<u64> {
+        self.checked_combine(a.iter().zip(b).map(|(&a, &b)| a.abs_diff(b)))
+    }
+}
+
+/// The square of the straight-line distance, which orders pairs the same way without needing a square root.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SquaredEuclidean;
+
+/// The sum of the distances along each axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Manhattan;
+
+/// The largest of the distances along each axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Chebyshev;
+
+impl Metric for SquaredEuclidean {
+    #[inline(always)]
+    fn combine_lanes(self, diffs: [u64; 4]) -> u64 {
+        // Squaring and adding up wraps on overflow, which still gives the exact sum whenever that fits.
+        let diffs = u64x4::from(diffs);
+        let squared: [u64; 4] = (diffs * diffs).into();
+        squared.into_iter().fold(0, u64::wrapping_add)
+    }
+
+    fn checked_combine(self, diffs: impl IntoIterator<Item = u64>) -> Option<u64> {
+        diffs
+            .into_iter()
+            .try_fold(0u64, |sum, d| sum.checked_add(d.checked_mul(d)?))
+    }
+
+    fn reach(self, distance: u64) -> u64 {
+        let root = distance.isqrt();
+        root + u64::from(root * root < distance)
+    }
+}
+
+impl Metric for Manhattan {
+    #[inline(always)]
+    fn combine_lanes(self, diffs: [u64; 4]) -> u64 {
+        diffs.into_iter().fold(0, u64::wrapping_add)
+    }
+
+    fn checked_combine(self, diffs: impl IntoIterator<Item = u64>) -> Option<u64> {
+        diffs.into_iter().try_fold(0u64, u64::checked_add)
+    }
+
+    fn reach(self, distance: u64) -> u64 {
+        distance
+    }
+}
+
+impl Metric for Chebyshev {
+    #[inline(always)]
+    fn combine_lanes(self, diffs: [u64; 4]) -> u64 {
+        diffs.into_iter().max().unwrap()
+    }
+
+    fn checked_combine(self, diffs: impl IntoIterator<Item = u64>) -> Option<u64> {
+        Some(diffs.into_iter().max().unwrap_or(0))
+    }
+
+    fn reach(self, distance: u64) -> u64 {
+        distance
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::prelude::*;
+use union_find::*;
+
+use crate::{Metric, SpatialIndex};
+
+/// How to find the minimum spanning tree that connects the boxes into a single circuit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Mst {
+    /// Connect the pairs shortest first whenever they join two circuits.
+    #[default]
+    Kruskal,
+    /// Connect every circuit to its closest neighbour at once, in parallel, without sorting the pairs.
+    Boruvka,
+}
+
+impl<const D: usize, M: Metric> SpatialIndex<D, M> {
+    /// Returns the pairs [`edges`](Self::edges) yields that join two circuits, shortest first.
+    pub fn spanning_tree(&self, mst: Mst) -> Vec<(u64, u32, u32)> {
+        match mst {
+            Mst::Kruskal => kruskal(self),
+            Mst::Boruvka => boruvka(self),
+        }
+    }
+}
+
+fn kruskal<const D: usize, M: Metric>(index: &SpatialIndex<D, M>) -> Vec<(u64, u32, u32)> {
+    let mut circuits = QuickUnionUf::<UnionBySize>::new(index.len());
+    let mut tree = Vec::with_capacity(index.len().saturating_sub(1));
+    for edge in index.edges() {
+        if tree.len() + 1 >= index.len() {
+            break;
+        }
+        if circuits.union(edge.1 as usize, edge.2 as usize) {
+            tree.push(edge);
+        }
+    }
+    tree
+}
+
+fn boruvka<const D: usize, M: Metric>(index: &SpatialIndex<D, M>) -> Vec<(u64, u32, u32)> {
+    let n = index.len();
+    // The circuit every box is in, named after one of its boxes.
+    let mut circuit = (0..n as u32).collect::<Vec<_>>();
+    let mut circuits = n;
+    let mut tree = Vec::with_capacity(n.saturating_sub(1));
+    let closest = (0..n).map(|_| AtomicUsize::new(usize::MAX)).collect::<Vec<_>>();
+
+    // Every pair in a band is shorter than every pair in the next, so connecting up the circuits band by band finds
+    // the same tree as connecting them all at once.
+    for (above, up_to) in index.bands() {
+        if circuits <= 1 {
+            break;
+        }
+        let mut edges = index.pairs_within(above, up_to, |i, j| circuit[i as usize] != circuit[j as usize]);
+
+        while !edges.is_empty() {
+            // Find the shortest pair leaving every circuit.
+            edges.par_iter().enumerate().for_each(|(e, &(_, i, j))| {
+                for c in [circuit[i as usize], circuit[j as usize]] {
+                    let _ = closest[c as usize].fetch_update(Ordering::Relaxed, Ordering::Relaxed, |best| {
+                        (best == usize::MAX || edges[e] < edges[best]).then_some(e)
+                    });
+                }
+            });
+            let picked = closest
+                .par_iter()
+                .map(|e| e.swap(usize::MAX, Ordering::Relaxed))
+                .collect::<Vec<_>>();
+
+            // Hook every circuit onto the one at the other end of its shortest pair. Two circuits that pick each other
+            // must have picked the same pair, so the lower-named one stays put to connect it only once.
+            let mut parent = (0..n as u32)
+                .into_par_iter()
+                .map(|c| {
+                    let Some(&(_, i, j)) = edges.get(picked[c as usize]) else {
+                        return c;
+                    };
+                    let (a, b) = (circuit[i as usize], circuit[j as usize]);
+                    let other = if a == c { b } else { a };
+                    if picked[other as usize] == picked[c as usize] && c < other {
+                        c
+                    } else {
+                        other
+                    }
+                })
+                .collect::<Vec<_>>();
+            let connected = tree.len();
+            tree.par_extend(
+                (0..n)
+                    .into_par_iter()
+                    .filter(|&c| parent[c] != c as u32)
+                    .map(|c| edges[picked[c]]),
+            );
+            circuits -= tree.len() - connected;
+
+            // Every circuit is now a tree of hooks with a single circuit at the root, whose name they all take.
+            loop {
+                let grandparent = parent.par_iter().map(|&p| parent[p as usize]).collect::<Vec<_>>();
+                if grandparent == parent {
+                    break;
+                }
+                parent = grandparent;
+            }
+            circuit.par_iter_mut().for_each(|c| *c = parent[*c as usize]);
+            edges = edges
+                .into_par_iter()
+                .filter(|&(_, i, j)| circuit[i as usize] != circuit[j as usize])
+                .collect();
+        }
+    }
+
+    tree.par_sort_unstable();
+    tree
+}
@@ -0,0 +1,82 @@
+use std::{error::Error, fmt};
+
+use atoi::FromRadix10SignedChecked;
+
+use crate::Metric;
+
+/// Lines and columns count from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A box with the wrong number of coordinates.
+    WrongDimension { line: usize, expected: usize, found: usize },
+    /// A coordinate that isn't an `i64`; `column` is where it starts.
+    InvalidCoordinate { line: usize, column: usize },
+    /// A box whose distance from an earlier one doesn't fit in a `u64`.
+    TooFarApart { line: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::WrongDimension { line, expected, found } => {
+                write!(f, "line {line}: expected {expected} coordinates, found {found}")
+            }
+            ParseError::InvalidCoordinate { line, column } => {
+                write!(f, "line {line}, column {column}: invalid coordinate")
+            }
+            ParseError::TooFarApart { line } => {
+                write!(
+                    f,
+                    "line {line}: junction box too far from the others to measure the distance"
+                )
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses one junction box per line as `D` comma-separated coordinates, checking their distances fit in a `u64`.
+pub fn parse_boxes<const D: usize>(input: &str, metric: impl Metric) -> Result<Vec<[i64; D]>, ParseError> {
+    let mut boxes = Vec::new();
+    let (mut min, mut max) = ([i64::MAX; D], [i64::MIN; D]);
+
+    for (line, text) in input.lines().enumerate().map(|(idx, text)| (idx + 1, text)) {
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let found = text.split(',').count();
+        if found != D {
+            return Err(ParseError::WrongDimension {
+                line,
+                expected: D,
+                found,
+            });
+        }
+
+        let mut b = [0; D];
+        let mut column = 1;
+        for (coordinate, field) in b.iter_mut().zip(text.split(',')) {
+            let digits = field.trim();
+            let unsigned = digits.strip_prefix(['-', '+']).unwrap_or(digits);
+            match i64::from_radix_10_signed_checked(digits.as_bytes()) {
+                (Some(n), used) if used == digits.len() && !unsigned.is_empty() => *coordinate = n,
+                _ => return Err(ParseError::InvalidCoordinate { line, column }),
+            }
+            column += field.len() + 1;
+        }
+
+        // The bounding box's opposite corners are the furthest apart any two boxes can be.
+        for axis in 0..D {
+            min[axis] = min[axis].min(b[axis]);
+            max[axis] = max[axis].max(b[axis]);
+        }
+        if metric.checked_distance(&min, &max).is_none() {
+            return Err(ParseError::TooFarApart { line });
+        }
+        boxes.push(b);
+    }
+
+    Ok(boxes)
+}
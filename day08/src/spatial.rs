@@ -0,0 +1,289 @@
+use std::{cmp::Reverse, error::Error, fmt, ops::Range, vec};
+
+use rayon::prelude::*;
+
+use crate::Metric;
+
+/// A uniform grid with about one junction box per cell, for finding close pairs without trying them all.
+#[derive(Clone, Debug)]
+pub struct SpatialIndex<const D: usize, M> {
+    metric: M,
+    min: [i64; D],
+    /// The axis of the boxes each axis of the grid runs along; only the three most spread out are gridded.
+    axes: [Option<usize>; 3],
+    cell: u64,
+    dims: [usize; 3],
+    /// Box indices sorted by cell, with `order[starts[c]..starts[c + 1]]` being the boxes in cell `c`.
+    order: Vec<u32>,
+    starts: Vec<u32>,
+    sorted: Vec<[i64; D]>,
+    diameter: u64,
+}
+
+const MAX_CELLS_PER_BOX: usize = 2;
+
+impl<const D: usize, M: Metric> SpatialIndex<D, M> {
+    /// Panics if there are more boxes than fit in a `u32`.
+    pub fn new(boxes: &[[i64; D]], metric: M) -> Result<Self, TooFarApart> {
+        assert!(u32::try_from(boxes.len()).is_ok(), "too many junction boxes");
+
+        let fold = |f: fn(i64, i64) -> i64| move |a: [i64; D], b: [i64; D]| std::array::from_fn(|i| f(a[i], b[i]));
+        let min = boxes.iter().copied().reduce(fold(i64::min)).unwrap_or([0; D]);
+        let max = boxes.iter().copied().reduce(fold(i64::max)).unwrap_or([0; D]);
+        let extents: [u64; D] = std::array::from_fn(|i| max[i].abs_diff(min[i]));
+        let diameter = metric.checked_distance(&min, &max).ok_or(TooFarApart)?;
+
+        let mut by_extent = (0..D).collect::<Vec<_>>();
+        by_extent.sort_by_key(|&axis| Reverse(extents[axis]));
+        let axes: [Option<usize>; 3] = std::array::from_fn(|g| by_extent.get(g).copied());
+        let grid_extents = axes.map(|axis| axis.map_or(0, |axis| extents[axis]));
+
+        // Split the volume spanned by the boxes evenly between them, ignoring any axes they're all lined up along.
+        let spread = grid_extents.iter().filter(|&&e| e > 0);
+        let volume = spread.clone().map(|&e| e as f64).product::<f64>();
+        let cell = (volume / boxes.len().max(1) as f64).powf(1.0 / spread.count().max(1) as f64);
+        let mut cell = (cell.ceil() as u64).max(1);
+        let dims = loop {
+            let dims = grid_extents.map(|e| (e / cell) as usize + 1);
+            let cells = dims.iter().try_fold(1usize, |cells, &dim| cells.checked_mul(dim));
+            // Once cells are wider than every extent there's only one of them, unless an extent is as wide as a `u64`
+            // goes, in which case two cells along it is as few as there can be.
+            if cells.is_some_and(|cells| cells <= boxes.len().max(1) * MAX_CELLS_PER_BOX) || cell == u64::MAX {
+                break dims;
+            }
+            cell = cell.saturating_add(cell.div_ceil(4));
+        };
+
+        let mut index = Self {
+            metric,
+            min,
+            axes,
+            cell,
+            dims,
+            order: Vec::new(),
+            starts: Vec::new(),
+            sorted: Vec::new(),
+            diameter,
+        };
+
+        // Counting sort of the boxes by cell.
+        let cells = boxes.iter().map(|b| index.cell_of(b)).collect::<Vec<_>>();
+        let mut starts = vec![0u32; dims.iter().product::<usize>() + 1];
+        for &c in &cells {
+            starts[c + 1] += 1;
+        }
+        for c in 1..starts.len() {
+            starts[c] += starts[c - 1];
+        }
+        let mut next = starts.clone();
+        let mut order = vec![0u32; boxes.len()];
+        for (i, &c) in cells.iter().enumerate() {
+            order[next[c] as usize] = i as u32;
+            next[c] += 1;
+        }
+
+        index.sorted = order.iter().map(|&i| boxes[i as usize]).collect();
+        index.order = order;
+        index.starts = starts;
+        Ok(index)
+    }
+
+    fn cell_of(&self, b: &[i64; D]) -> usize {
+        let [x, y, z] = self
+            .axes
+            .map(|axis| axis.map_or(0, |axis| (b[axis].abs_diff(self.min[axis]) / self.cell) as usize));
+        (z * self.dims[1] + y) * self.dims[0] + x
+    }
+
+    fn slots(&self, c: usize) -> Range<usize> {
+        self.starts[c] as usize..self.starts[c + 1] as usize
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Iterates over every pair of boxes as `(distance, i, j)` with `i < j`, shortest first, breaking ties by index.
+    pub fn edges(&self) -> Edges<'_, D, M> {
+        Edges {
+            index: self,
+            bands: self.bands(),
+            pending: Vec::new().into_iter(),
+        }
+    }
+
+    pub(crate) fn bands(&self) -> Bands {
+        Bands {
+            next: (None, self.metric.combine([self.cell])),
+            diameter: self.diameter,
+        }
+    }
+
+    fn edges_within(&self, above: Option<u64>, up_to: u64) -> Vec<(u64, u32, u32)> {
+        let mut edges = self.pairs_within(above, up_to, |_, _| true);
+        edges.par_sort_unstable();
+        edges
+    }
+
+    /// Returns the unsorted pairs in the band from `above` to `up_to` for which `keep(i, j)` holds.
+    pub(crate) fn pairs_within(
+        &self,
+        above: Option<u64>,
+        up_to: u64,
+        keep: impl Fn(u32, u32) -> bool + Sync,
+    ) -> Vec<(u64, u32, u32)> {
+        self.fold_pairs(
+            above,
+            up_to,
+            Vec::new,
+            |edges, d, i, j| {
+                if keep(i, j) {
+                    edges.push((d, i, j));
+                }
+            },
+            |mut edges, mut more| {
+                edges.append(&mut more);
+                edges
+            },
+        )
+    }
+
+    /// Returns how many pairs come no later than `last` in the order [`edges`](Self::edges) yields them.
+    pub(crate) fn rank(&self, last: (u64, u32, u32)) -> usize {
+        self.fold_pairs(
+            None,
+            last.0,
+            || 0,
+            |count, d, i, j| *count += usize::from((d, i, j) <= last),
+            |a, b| a + b,
+        )
+    }
+
+    fn fold_pairs<T: Send>(
+        &self,
+        above: Option<u64>,
+        up_to: u64,
+        identity: impl Fn() -> T + Sync + Send,
+        visit: impl Fn(&mut T, u64, u32, u32) + Sync + Send,
+        merge: impl Fn(T, T) -> T + Sync + Send,
+    ) -> T {
+        let radius = self.metric.reach(up_to);
+        let [rx, ry, rz] = self
+            .dims
+            .map(|dim| radius.div_ceil(self.cell).min(dim as u64 - 1) as isize);
+
+        // Offsets to the cells that can hold a box in the band around one in the cell at the origin. Only the forward
+        // half is needed, since the backward half finds the same pairs from the other end. Pairs can only be ruled
+        // out for being too close if the grid covers every axis, since the others could add any distance.
+        let gaps = |offset: [isize; 3], extra: isize| {
+            offset.map(|d| ((d.unsigned_abs() as isize + extra).max(0) as u64).saturating_mul(self.cell))
+        };
+        let gridded = self.axes.iter().flatten().count() == D;
+        let mut offsets = Vec::new();
+        for dz in 0..=rz {
+            for dy in -ry..=ry {
+                for dx in -rx..=rx {
+                    let offset = [dx, dy, dz];
+                    let nearest = self.metric.combine(gaps(offset, -1));
+                    let furthest = self.metric.combine(gaps(offset, 1));
+                    let too_close = gridded && above.is_some_and(|above| furthest <= above);
+                    if (dz, dy, dx) >= (0, 0, 0) && nearest <= up_to && !too_close {
+                        offsets.push(offset);
+                    }
+                }
+            }
+        }
+
+        let in_band = |d: u64| above.is_none_or(|above| d > above) && d <= up_to;
+        (0..self.starts.len() - 1)
+            .into_par_iter()
+            .filter(|&c| self.starts[c] < self.starts[c + 1])
+            .fold(&identity, |mut acc, c| {
+                let [x, y] = [c % self.dims[0], c / self.dims[0] % self.dims[1]];
+                let z = c / self.dims[0] / self.dims[1];
+                for &[dx, dy, dz] in &offsets {
+                    let (Some(nx), Some(ny), Some(nz)) = (
+                        x.checked_add_signed(dx).filter(|&nx| nx < self.dims[0]),
+                        y.checked_add_signed(dy).filter(|&ny| ny < self.dims[1]),
+                        z.checked_add_signed(dz).filter(|&nz| nz < self.dims[2]),
+                    ) else {
+                        continue;
+                    };
+                    let n = (nz * self.dims[1] + ny) * self.dims[0] + nx;
+
+                    for a in self.slots(c) {
+                        // Within a cell, only pair each box with the ones after it.
+                        let others = if n == c {
+                            a + 1..self.slots(c).end
+                        } else {
+                            self.slots(n)
+                        };
+                        for b in others {
+                            let d = self.metric.distance(&self.sorted[a], &self.sorted[b]);
+                            if in_band(d) {
+                                let (i, j) = (self.order[a], self.order[b]);
+                                visit(&mut acc, d, i.min(j), i.max(j));
+                            }
+                        }
+                    }
+                }
+                acc
+            })
+            .reduce(&identity, merge)
+    }
+}
+
+/// Junction boxes spread out far enough that the distances between them don't all fit in a `u64`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooFarApart;
+
+impl fmt::Display for TooFarApart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "junction boxes too far apart to measure the distances between them")
+    }
+}
+
+impl Error for TooFarApart {}
+
+/// Every pair of boxes in a [`SpatialIndex`], shortest first.
+#[derive(Clone, Debug)]
+pub struct Edges<'a, const D: usize, M> {
+    index: &'a SpatialIndex<D, M>,
+    bands: Bands,
+    pending: vec::IntoIter<(u64, u32, u32)>,
+}
+
+impl<const D: usize, M: Metric> Iterator for Edges<'_, D, M> {
+    type Item = (u64, u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(edge) = self.pending.next() {
+                return Some(edge);
+            }
+
+            let (above, up_to) = self.bands.next()?;
+            self.pending = self.index.edges_within(above, up_to).into_iter();
+        }
+    }
+}
+
+/// Ranges of distances as `(above, up_to)`, doubling in length until they cover every pair of boxes.
+#[derive(Clone, Debug)]
+pub(crate) struct Bands {
+    next: (Option<u64>, u64),
+    diameter: u64,
+}
+
+impl Iterator for Bands {
+    type Item = (Option<u64>, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (above, up_to) = self.next;
+        if above.is_some_and(|above| above >= self.diameter) {
+            return None;
+        }
+        self.next = (Some(up_to), up_to.saturating_mul(2));
+        Some((above, up_to))
+    }
+}
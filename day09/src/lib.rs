@@ -1,7 +1,6 @@
 use std::fmt::Display;
 
 use itertools::Itertools;
-use rayon::prelude::*;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 struct Point {
@@ -71,20 +70,6 @@ impl From<(Point, Point)> for Segment {
     }
 }
 
-impl Segment {
-    fn is_horizontal(&self) -> bool {
-        self.start.x != self.end.x
-    }
-
-    fn len(&self) -> u64 {
-        if self.is_horizontal() {
-            self.end.x - self.start.x + 1
-        } else {
-            self.end.y - self.start.y + 1
-        }
-    }
-}
-
 #[inline]
 pub fn solve() -> (impl Display, impl Display) {
     let input = include_str!("input.txt");
@@ -96,18 +81,7 @@ pub fn solve() -> (impl Display, impl Display) {
         })
         .collect_vec();
 
-    let segments = points
-        .iter()
-        .copied()
-        .circular_tuple_windows::<(Point, Point)>()
-        .map(Segment::from)
-        .sorted_unstable_by_key(|s| s.len())
-        .collect_vec();
-
-    rayon::join(
-        || solve_part1(points.iter().copied()),
-        || solve_part2(&points, &segments),
-    )
+    rayon::join(|| solve_part1(points.iter().copied()), || solve_part2(&points))
 }
 
 fn solve_part1(points: impl Iterator<Item = Point> + Clone) -> u64 {
@@ -119,22 +93,275 @@ fn solve_part1(points: impl Iterator<Item = Point> + Clone) -> u64 {
         .unwrap()
 }
 
-// https://www.reddit.com/r/adventofcode/comments/1phywvn/2025_day_9_solutions/nt64t2d/
-fn solve_part2(points: &[Point], segments: &[Segment]) -> u64 {
+fn solve_part2(points: &[Point]) -> u64 {
+    let floor = Floor::new(points);
     points
         .iter()
         .copied()
         .tuple_combinations::<(_, _)>()
         .map(Rect::from)
-        .sorted_unstable_by_key(|r| std::cmp::Reverse(r.area()))
-        .collect_vec()
-        .into_par_iter()
-        .find_first(|rect: &Rect| {
-            !segments.iter().any(|segment: &Segment| {
-                (segment.start.x < rect.x_max && segment.end.x > rect.x_min)
-                    && (segment.start.y < rect.y_max && segment.end.y > rect.y_min)
-            })
-        })
+        .filter(|&rect| floor.is_covered(rect))
+        .map(Rect::area)
+        .max()
         .unwrap()
-        .area()
+}
+
+/// The tiles in and around the loop, compressed so that every corner coordinate of the loop gets a row or column of
+/// its own, and so does every run of coordinates in between two of them. All the tiles in a compressed cell are either
+/// on the loop, inside it or outside it together, and two cells are next to each other exactly when their tiles are.
+struct Floor {
+    /// The first coordinate of every compressed column and row.
+    xs: Vec<u64>,
+    ys: Vec<u64>,
+    /// Prefix sums of the cells outside the loop, padded all round.
+    outside: Vec<u32>,
+    width: usize,
+}
+
+impl Floor {
+    fn new(points: &[Point]) -> Self {
+        let compress = |coords: Vec<u64>| {
+            let mut starts = Vec::with_capacity(coords.len() * 2);
+            for (i, &c) in coords.iter().enumerate() {
+                starts.push(c);
+                if coords.get(i + 1).is_some_and(|&next| next > c + 1) {
+                    starts.push(c + 1);
+                }
+            }
+            starts
+        };
+        let xs = compress(points.iter().map(|p| p.x).sorted_unstable().dedup().collect());
+        let ys = compress(points.iter().map(|p| p.y).sorted_unstable().dedup().collect());
+        let (width, height) = (xs.len() + 2, ys.len() + 2);
+        let mut floor = Self {
+            xs,
+            ys,
+            outside: Vec::new(),
+            width,
+        };
+
+        let mut on_loop = vec![false; width * height];
+        for segment in points
+            .iter()
+            .copied()
+            .circular_tuple_windows::<(Point, Point)>()
+            .map(Segment::from)
+        {
+            let (start, end) = (floor.cell(segment.start), floor.cell(segment.end));
+            for y in start.1..=end.1 {
+                on_loop[y * width + start.0..=y * width + end.0].fill(true);
+            }
+        }
+
+        // Flood the outside in from the padding.
+        let mut outside = vec![false; width * height];
+        let mut stack = vec![0];
+        while let Some(c) = stack.pop() {
+            if outside[c] || on_loop[c] {
+                continue;
+            }
+            outside[c] = true;
+            let (x, y) = (c % width, c / width);
+            if x > 0 {
+                stack.push(c - 1);
+            }
+            if x + 1 < width {
+                stack.push(c + 1);
+            }
+            if y > 0 {
+                stack.push(c - width);
+            }
+            if y + 1 < height {
+                stack.push(c + width);
+            }
+        }
+
+        let w = width + 1;
+        floor.outside = vec![0; w * (height + 1)];
+        for y in 0..height {
+            for x in 0..width {
+                floor.outside[(y + 1) * w + x + 1] =
+                    u32::from(outside[y * width + x]) + floor.outside[y * w + x + 1] + floor.outside[(y + 1) * w + x]
+                        - floor.outside[y * w + x];
+            }
+        }
+        floor
+    }
+
+    /// Returns the compressed cell of a corner of the loop, as `(column, row)`.
+    fn cell(&self, point: Point) -> (usize, usize) {
+        let index = |starts: &[u64], c| starts.binary_search(&c).unwrap() + 1;
+        (index(&self.xs, point.x), index(&self.ys, point.y))
+    }
+
+    /// Returns whether every tile of `rect`, whose corners are both corners of the loop, is on the loop or inside it.
+    fn is_covered(&self, rect: Rect) -> bool {
+        let (x0, y0) = self.cell(Point(rect.x_min, rect.y_min));
+        let (x1, y1) = self.cell(Point(rect.x_max, rect.y_max));
+        let w = self.width + 1;
+        let sum = |x: usize, y: usize| self.outside[y * w + x];
+        sum(x1 + 1, y1 + 1) + sum(x0, y0) == sum(x0, y1 + 1) + sum(x1 + 1, y0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An xorshift generator, so that the random loops are the same on every run.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    /// Returns the corners of a random polyomino's outline, or `None` if it isn't a simple loop.
+    fn random_outline(rng: &mut Rng, width: usize, height: usize, size: usize) -> Option<Vec<(usize, usize)>> {
+        let mut filled = vec![false; width * height];
+        let mut cells = vec![(rng.below(width), rng.below(height))];
+        filled[cells[0].1 * width + cells[0].0] = true;
+        for _ in 1..size {
+            let (x, y) = cells[rng.below(cells.len())];
+            let (x, y) = match rng.below(4) {
+                0 => (x + 1, y),
+                1 => (x.wrapping_sub(1), y),
+                2 => (x, y + 1),
+                _ => (x, y.wrapping_sub(1)),
+            };
+            if x < width && y < height && !filled[y * width + x] {
+                filled[y * width + x] = true;
+                cells.push((x, y));
+            }
+        }
+
+        // The sides of the cells that aren't shared with another cell, as the corners they join.
+        let is_filled = |x: usize, y: usize| x < width && y < height && filled[y * width + x];
+        let mut neighbours = vec![Vec::new(); (width + 1) * (height + 1)];
+        let mut join = |(ax, ay): (usize, usize), (bx, by): (usize, usize)| {
+            neighbours[ay * (width + 1) + ax].push((bx, by));
+            neighbours[by * (width + 1) + bx].push((ax, ay));
+        };
+        let mut sides = 0;
+        for &(x, y) in &cells {
+            let outward = [
+                (y == 0 || !is_filled(x, y - 1), (x, y), (x + 1, y)),
+                (!is_filled(x, y + 1), (x, y + 1), (x + 1, y + 1)),
+                (x == 0 || !is_filled(x - 1, y), (x, y), (x, y + 1)),
+                (!is_filled(x + 1, y), (x + 1, y), (x + 1, y + 1)),
+            ];
+            for (open, a, b) in outward {
+                if open {
+                    join(a, b);
+                    sides += 1;
+                }
+            }
+        }
+        if neighbours.iter().any(|n| !n.is_empty() && n.len() != 2) {
+            return None;
+        }
+
+        // Walk round the outline; if that doesn't use every side, the polyomino has holes.
+        let start = neighbours.iter().position(|n| !n.is_empty()).unwrap();
+        let start = (start % (width + 1), start / (width + 1));
+        let mut outline = vec![start];
+        let (mut previous, mut current) = (start, neighbours[start.1 * (width + 1) + start.0][0]);
+        while current != start {
+            outline.push(current);
+            let next = &neighbours[current.1 * (width + 1) + current.0];
+            let next = if next[0] == previous { next[1] } else { next[0] };
+            (previous, current) = (current, next);
+        }
+        if outline.len() != sides {
+            return None;
+        }
+
+        let n = outline.len();
+        let is_corner = |i: usize| {
+            let (a, b, c) = (outline[(i + n - 1) % n], outline[i], outline[(i + 1) % n]);
+            !(a.0 == b.0 && b.0 == c.0 || a.1 == b.1 && b.1 == c.1)
+        };
+        Some((0..n).filter(|&i| is_corner(i)).map(|i| outline[i]).collect())
+    }
+
+    /// Part 2 the slow way, checking every tile of every rectangle.
+    fn brute_force(points: &[Point]) -> u64 {
+        // Shift everything over by one to leave room for the outside all round.
+        let width = points.iter().map(|p| p.x).max().unwrap() as usize + 3;
+        let height = points.iter().map(|p| p.y).max().unwrap() as usize + 3;
+        let mut on_loop = vec![false; width * height];
+        for segment in points
+            .iter()
+            .copied()
+            .circular_tuple_windows::<(Point, Point)>()
+            .map(Segment::from)
+        {
+            for y in segment.start.y..=segment.end.y {
+                for x in segment.start.x..=segment.end.x {
+                    on_loop[(y as usize + 1) * width + x as usize + 1] = true;
+                }
+            }
+        }
+
+        let mut outside = vec![false; width * height];
+        let mut stack = vec![(0, 0)];
+        while let Some((x, y)) = stack.pop() {
+            if x >= width || y >= height || outside[y * width + x] || on_loop[y * width + x] {
+                continue;
+            }
+            outside[y * width + x] = true;
+            stack.extend([(x + 1, y), (x.wrapping_sub(1), y), (x, y + 1), (x, y.wrapping_sub(1))]);
+        }
+
+        points
+            .iter()
+            .copied()
+            .tuple_combinations::<(_, _)>()
+            .map(Rect::from)
+            .filter(|rect| {
+                (rect.y_min..=rect.y_max)
+                    .cartesian_product(rect.x_min..=rect.x_max)
+                    .all(|(y, x)| !outside[(y as usize + 1) * width + x as usize + 1])
+            })
+            .map(Rect::area)
+            .max()
+            .unwrap()
+    }
+
+    #[test]
+    fn notch_without_tiles_in_it() {
+        let points = [(0, 0), (10, 0), (10, 10), (6, 10), (6, 5), (5, 5), (5, 10), (0, 10)].map(|(x, y)| Point(x, y));
+        assert_eq!(solve_part2(&points), 121);
+    }
+
+    #[test]
+    fn matches_brute_force_on_random_loops() {
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        let mut checked = 0;
+        while checked < 2000 {
+            let (width, height) = (2 + rng.below(8), 2 + rng.below(8));
+            let size = 2 + rng.below(width * height);
+            let Some(outline) = random_outline(&mut rng, width, height, size) else {
+                continue;
+            };
+
+            // Space the corners out unevenly, mostly leaving no tiles in between to catch loops running side by side.
+            let mut spacing = |len: usize| {
+                let mut coords = vec![rng.below(3) as u64];
+                for _ in 0..len {
+                    coords.push(coords.last().unwrap() + [1, 1, 1, 2, 3][rng.below(5)]);
+                }
+                coords
+            };
+            let (xs, ys) = (spacing(width), spacing(height));
+            let points = outline.iter().map(|&(x, y)| Point(xs[x], ys[y])).collect_vec();
+
+            assert_eq!(solve_part2(&points), brute_force(&points), "{points:?}");
+            checked += 1;
+        }
+    }
 }